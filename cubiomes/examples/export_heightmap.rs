@@ -0,0 +1,42 @@
+// Export the heightmap as a 16 bit image and as raw floats for other tools
+
+use std::{fs::File, io::BufWriter};
+
+use cubiomes::{
+    enums::{Dimension, MCVersion},
+    generator::{Generator, GeneratorFlags},
+    noise::BiomeNoise,
+};
+
+fn main() {
+    let seed: i64 = -4804349823814383506;
+    let mc_version = MCVersion::MC_1_21_WD;
+
+    let generator = Generator::new(
+        mc_version,
+        seed,
+        Dimension::DIM_OVERWORLD,
+        GeneratorFlags::empty(),
+    );
+    let surface_noise = BiomeNoise::new(mc_version, Dimension::DIM_OVERWORLD, seed);
+
+    // A 1024x1024 block area, as the heightmap is at a 1:4 scale
+    let heightmap = generator
+        .heightmap(256, 1024, 256, 256, &surface_noise)
+        .expect("Overworld should always have heights");
+
+    // The height at a block position inside the area
+    let height = heightmap.height_at(1030, 4100);
+    assert_eq!(height, heightmap.get(1, 1));
+
+    // A 16 bit image doesn't have visible bands like an 8 bit one
+    let (bottom, top) = heightmap.min_max().expect("The heightmap isn't empty");
+    heightmap
+        .to_gray16_image(bottom, top)
+        .save("heightmap16.png")
+        .expect("Failed to write image");
+
+    // Raw little endian floats, row by row from north to south
+    let file = BufWriter::new(File::create("heightmap.raw").expect("Failed to open file"));
+    heightmap.write_raw(file).expect("Failed to write file");
+}
@@ -14,9 +14,8 @@ fn main() {
         GeneratorFlags::empty(),
     );
 
-    let mut strucutre_region =
-        StructureRegion::new(0, 0, generator.minecraft_version(), STRUCTURE_TYPE)
-            .expect("we passed a valid structure for the version");
+    let mut strucutre_region = StructureRegion::new(0, 0, MINECRAFT_VERSION, STRUCTURE_TYPE)
+        .expect("we passed a valid structure for the version");
 
     let mut mansion_positions: Vec<BlockPosition> = Vec::new();
 
@@ -26,7 +25,10 @@ fn main() {
         for z in -10..10 {
             strucutre_region.z = z;
 
-            if let Some(pos) = generator.try_generate_structure_in_region(strucutre_region) {
+            if let Some(pos) = generator
+                .try_generate_structure_in_region(strucutre_region)
+                .expect("the region is for the same version as the generator")
+            {
                 mansion_positions.push(pos);
             }
         }
@@ -38,7 +38,7 @@ fn main() {
 
     let img = generator
         .generate_heightmap_image(256, 1024, 256, 256, 40.0, 100.0, &surface_noise.into())
-        .expect("Overworld should always exist");
+        .expect("Overworld should always have heights");
 
     sleep(Duration::from_millis(100).saturating_sub(now.elapsed()));
 
@@ -48,7 +48,9 @@ fn main() {
     .expect("Failed to generate cache");
 
     // Make an image buffer from the cache
-    let img = cache.to_image(BiomeColorMap::new());
+    let img = cache
+        .to_image(BiomeColorMap::new())
+        .expect("The cache only contains valid biomes");
 
     // Open a file
     let mut file = BufWriter::new(File::create(path).expect("Failed to open file"));
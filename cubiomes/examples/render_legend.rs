@@ -0,0 +1,52 @@
+use cubiomes::{
+    colors::BiomeColorMap,
+    enums::{Dimension, MCVersion},
+    generator::{Cache, Generator, GeneratorFlags, Range, Scale},
+    render::{Annotations, GridLines, LegendOptions, ScaleBar},
+};
+
+fn main() {
+    let generator = Generator::new(
+        MCVersion::MC_1_21_WD,
+        -4804349823814383506,
+        Dimension::DIM_OVERWORLD,
+        GeneratorFlags::empty(),
+    );
+
+    let range = Range {
+        scale: Scale::Quad,
+        x: -256,
+        z: -256,
+        size_x: 512,
+        size_z: 512,
+        y: 16,
+        size_y: 0,
+    };
+
+    let cache = Cache::new(&generator, range).expect("The overworld supports 1:4");
+
+    let mut map = cache
+        .to_image(BiomeColorMap::new())
+        .expect("The cache only contains valid biomes");
+
+    let annotations = Annotations {
+        grid: Some(GridLines {
+            spacing: 256,
+            color: [255, 255, 255],
+            labels: true,
+        }),
+        region_borders: Some([0, 0, 0]),
+        scale_bar: Some(ScaleBar::default()),
+        ..Default::default()
+    };
+
+    annotations.draw(&mut map, &range);
+
+    // Every biome on the map, from the most to the least common
+    let legend = cache
+        .to_legend_image(BiomeColorMap::new(), &LegendOptions::default())
+        .expect("The cache only contains valid biomes");
+
+    map.save("annotated.png").expect("Failed to write image");
+    legend.save("legend.png").expect("Failed to write image");
+}
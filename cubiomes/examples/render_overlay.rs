@@ -0,0 +1,55 @@
+use cubiomes::{
+    colors::BiomeColorMap,
+    enums::{Dimension, MCVersion, StructureType},
+    generator::{Cache, Generator, GeneratorFlags, Range, Scale},
+    render::MapOverlay,
+};
+
+fn main() {
+    let seed: i64 = -4804349823814383506;
+
+    let mut generator = Generator::new(
+        MCVersion::MC_1_21_WD,
+        seed,
+        Dimension::DIM_OVERWORLD,
+        GeneratorFlags::empty(),
+    );
+
+    // 4096 by 4096 blocks around the origin, one pixel per chunk
+    let range = Range {
+        scale: Scale::Chunk,
+        x: -128,
+        z: -128,
+        size_x: 256,
+        size_z: 256,
+        y: 64,
+        size_y: 0,
+    };
+
+    // The cache borrows the generator, so drop it once the map is made
+    let mut img = Cache::new(&generator, range)
+        .expect("The overworld supports 1:16")
+        .to_image(BiomeColorMap::new())
+        .expect("The cache only contains valid biomes");
+
+    let mut overlay = MapOverlay::new(range);
+
+    for structure_type in [
+        StructureType::Village,
+        StructureType::Swamp_Hut,
+        StructureType::Monument,
+    ] {
+        overlay
+            .add_structures(&mut generator, structure_type)
+            .expect("The structures exist in 1.21");
+    }
+
+    overlay.add_strongholds(&generator);
+    overlay
+        .add_spawn(&generator)
+        .expect("The generator is in the overworld");
+
+    overlay.draw(&mut img);
+
+    img.save("overlay.png").expect("Failed to write image");
+}
@@ -0,0 +1,49 @@
+use cubiomes::{
+    colors::BiomeColorMap,
+    enums::{Dimension, MCVersion},
+    generator::{Cache, Generator, GeneratorFlags, Range, Scale},
+    noise::BiomeNoise,
+    render::ReliefOptions,
+};
+
+fn main() {
+    let seed: i64 = -4804349823814383506;
+    let version = MCVersion::MC_1_21_WD;
+
+    let generator = Generator::new(
+        version,
+        seed,
+        Dimension::DIM_OVERWORLD,
+        GeneratorFlags::empty(),
+    );
+
+    // Heights are only available at a 1:4 scale, so the map is too
+    let range = Range {
+        scale: Scale::Quad,
+        x: -128,
+        z: -128,
+        size_x: 256,
+        size_z: 256,
+        y: 16,
+        size_y: 0,
+    };
+
+    let cache = Cache::new(&generator, range).expect("The overworld supports 1:4");
+
+    let options = ReliefOptions {
+        // Light from the west in the evening
+        sun_azimuth: 270.0,
+        sun_altitude: 30.0,
+        ..Default::default()
+    };
+
+    let img = cache
+        .to_relief_image(
+            &BiomeNoise::new(version, Dimension::DIM_OVERWORLD, seed),
+            BiomeColorMap::new(),
+            &options,
+        )
+        .expect("The overworld has heights");
+
+    img.save("relief.png").expect("Failed to write image");
+}
@@ -0,0 +1,39 @@
+use cubiomes::{
+    enums::{Dimension, MCVersion},
+    generator::{Generator, GeneratorFlags, Range, Scale},
+    tiles::TilePyramid,
+};
+
+fn main() {
+    let generator = Generator::new(
+        MCVersion::MC_1_21_WD,
+        -4804349823814383506,
+        Dimension::DIM_OVERWORLD,
+        GeneratorFlags::empty(),
+    );
+
+    // 2048 by 2048 blocks around the origin
+    let area = Range {
+        scale: Scale::Block,
+        x: -1024,
+        z: -1024,
+        size_x: 2048,
+        size_z: 2048,
+        y: 64,
+        size_y: 0,
+    };
+
+    // Zoom 4 is one pixel per 4 blocks, zoom 0 one pixel per 64 blocks
+    let pyramid = TilePyramid {
+        min_zoom: 0,
+        max_zoom: 4,
+        finest_scale: Scale::Quad,
+        ..Default::default()
+    };
+
+    let written = pyramid
+        .render(&generator, area, "tiles".as_ref())
+        .expect("The overworld supports every scale of the pyramid");
+
+    println!("Wrote {written} tiles");
+}
@@ -1,5 +1,4 @@
-use cubiomes::noise::SurfaceNoiseRelease;
-use image::GrayImage;
+use cubiomes::noise::{NoiseArea, Normalization, SurfaceNoiseRelease};
 
 fn main() {
     let noise = SurfaceNoiseRelease::new(
@@ -7,9 +6,17 @@ fn main() {
         -4804349823814383506,
     );
 
-    let img = GrayImage::from_fn(256, 256, |x, y| {
-        [noise.sample_between(x as i32, 0, y as i32, 0.0, 255.0) as u8].into()
-    });
+    let grid = noise.sample_grid(NoiseArea::flat(0, 0, 0, 256, 256));
+
+    let stats = grid.stats().expect("the grid isn't empty");
+    println!(
+        "min: {}, max: {}, mean: {}",
+        stats.min, stats.max, stats.mean
+    );
+
+    let img = grid
+        .to_gray_image(0, Normalization::MinMax)
+        .expect("the grid has a layer 0");
 
     img.save("noise.png").expect("failed to write image");
 }
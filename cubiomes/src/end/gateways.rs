@@ -0,0 +1,145 @@
+//! Module for the end gateways around the main end island
+//!
+//! Each time the ender dragon is killed a new gateway spawns on a ring around
+//! the main island. The order of the 20 gateways is decided by the world seed.
+//! Entering a gateway teleports the player to the outer islands, roughly 1024
+//! blocks away in the direction of the gateway.
+//!
+//! # Examples
+//! ```
+//! use cubiomes::enums::{Dimension, MCVersion};
+//! use cubiomes::generator::{Generator, GeneratorFlags};
+//!
+//! let generator = Generator::new(
+//!     MCVersion::MC_1_21_WD,
+//!     4239805798134,
+//!     Dimension::DIM_END,
+//!     GeneratorFlags::empty(),
+//! );
+//!
+//! for gateway in generator.end_gateways().expect("the generator is in the end") {
+//!     println!("{:?} leads to {:?}", gateway.position, gateway.exit);
+//! }
+//! ```
+
+use crate::{
+    generator::{error::GeneratorError, BlockPosition, Generator},
+    noise::SurfaceNoiseRelease,
+    rng::JavaRandom,
+};
+use cubiomes_sys::enums::Dimension;
+
+/// Amount of gateways spawned around the main island
+pub const GATEWAY_COUNT: usize = 20;
+
+/// The y coordinate of the gateways around the main island
+pub const GATEWAY_Y: i32 = 75;
+
+/// A gateway around the main end island and its destination
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct EndGateway {
+    /// Position of the gateway around the main island
+    ///
+    /// The gateway is always at y [`GATEWAY_Y`].
+    pub position: BlockPosition,
+    /// The position on the outer islands the gateway leads to
+    ///
+    /// This is where the game starts looking for a spot for the exit gateway.
+    /// The exit gateway itself is placed on the tallest block within 16 blocks
+    /// of this position, which depends on blocks placed by decorations, so the
+    /// actual gateway may be slightly off.
+    pub exit: BlockPosition,
+}
+
+impl Generator {
+    /// Gets the positions of the gateways around the main island in the order
+    /// they spawn
+    ///
+    /// The positions are not affected by the terrain, so this is cheaper than
+    /// [`Self::end_gateways()`] if the exits are not needed.
+    pub fn end_gateway_positions(&self) -> Result<[BlockPosition; GATEWAY_COUNT], GeneratorError> {
+        self.ensure_end()?;
+
+        Ok(gateway_positions(self.seed()))
+    }
+
+    /// Gets the gateways around the main island in the order they spawn
+    /// together with their exits on the outer islands
+    pub fn end_gateways(&self) -> Result<Vec<EndGateway>, GeneratorError> {
+        let positions = self.end_gateway_positions()?;
+        let surface_noise = SurfaceNoiseRelease::new(Dimension::DIM_END, self.seed());
+
+        positions
+            .into_iter()
+            .map(|position| {
+                Ok(EndGateway {
+                    position,
+                    exit: self.find_gateway_exit(position, &surface_noise)?,
+                })
+            })
+            .collect()
+    }
+
+    /// Walks from 1024 blocks out in the direction of the gateway to the edge
+    /// of the outer islands, as the game does when the gateway is first used
+    fn find_gateway_exit(
+        &self,
+        gateway: BlockPosition,
+        surface_noise: &SurfaceNoiseRelease,
+    ) -> Result<BlockPosition, GeneratorError> {
+        let length = (gateway.x as f64).hypot(gateway.z as f64);
+        let direction = (gateway.x as f64 / length, gateway.z as f64 / length);
+
+        let is_empty = |pos: (f64, f64)| {
+            self.is_end_chunk_empty(
+                (pos.0 / 16.0).floor() as i32,
+                (pos.1 / 16.0).floor() as i32,
+                surface_noise,
+            )
+        };
+
+        let mut pos = (direction.0 * 1024.0, direction.1 * 1024.0);
+
+        // Step back towards the main island past chunks with terrain
+        let mut steps = 16;
+        while !is_empty(pos)? && steps > 0 {
+            steps -= 1;
+            pos = (pos.0 - direction.0 * 16.0, pos.1 - direction.1 * 16.0);
+        }
+
+        // Then step outwards until reaching a chunk with terrain
+        let mut steps = 16;
+        while is_empty(pos)? && steps > 0 {
+            steps -= 1;
+            pos = (pos.0 + direction.0 * 16.0, pos.1 + direction.1 * 16.0);
+        }
+
+        Ok(BlockPosition::new(
+            pos.0.floor() as i32,
+            pos.1.floor() as i32,
+        ))
+    }
+}
+
+/// Calculates the gateway positions in spawn order for a seed
+fn gateway_positions(seed: i64) -> [BlockPosition; GATEWAY_COUNT] {
+    let mut order: [i32; GATEWAY_COUNT] = std::array::from_fn(|idx| idx as i32);
+
+    // Same as Collections.shuffle in java
+    let mut rng = JavaRandom::new(seed);
+    for idx in (1..GATEWAY_COUNT).rev() {
+        order.swap(idx, rng.next_int(idx as i32 + 1) as usize);
+    }
+
+    // The gateways are taken from the end of the shuffled list
+    order.reverse();
+
+    order.map(|idx| {
+        let angle = 2.0 * (-std::f64::consts::PI + 0.15707963267948966 * idx as f64);
+
+        BlockPosition::new(
+            (96.0 * angle.cos()).floor() as i32,
+            (96.0 * angle.sin()).floor() as i32,
+        )
+    })
+}
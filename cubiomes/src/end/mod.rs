@@ -0,0 +1,42 @@
+//! Module for features of the end dimension
+//!
+//! Everything in this module requires a [`Generator`] which has been given
+//! [`Dimension::DIM_END`], either with [`Generator::new()`] or
+//! [`Generator::apply_seed()`].
+//!
+//! - For end gateways and the outer islands they lead to see [`gateways`]
+//! - For island heights and empty chunks see [`terrain`]
+//! - For the obsidian pillars around the main island see [`spikes`]
+
+use crate::generator::{error::GeneratorError, Generator};
+use cubiomes_sys::enums::{Dimension, MCVersion};
+
+pub mod gateways;
+pub mod spikes;
+pub mod terrain;
+
+#[cfg(test)]
+mod tests;
+
+impl Generator {
+    /// Checks that the generator is in the end of a version with the end
+    /// dimension as we know it (1.9 and later)
+    fn ensure_end(&self) -> Result<(), GeneratorError> {
+        let dimension = self.dimension()?;
+
+        if dimension != Dimension::DIM_END {
+            return Err(GeneratorError::WrongDimension {
+                expected: Dimension::DIM_END,
+                actual: dimension,
+            });
+        }
+
+        let version = self.minecraft_version()?;
+
+        if version < MCVersion::MC_1_9_4 {
+            return Err(GeneratorError::UnsupportedVersion(version));
+        }
+
+        Ok(())
+    }
+}
@@ -0,0 +1,85 @@
+//! Module for the obsidian pillars (end spikes) around the main end island
+//!
+//! The ten pillars are always in the same places on a ring around the exit
+//! portal, but their sizes are shuffled based on the world seed.
+//!
+//! # Examples
+//! ```
+//! use cubiomes::enums::{Dimension, MCVersion};
+//! use cubiomes::generator::{Generator, GeneratorFlags};
+//!
+//! let generator = Generator::new(
+//!     MCVersion::MC_1_21_WD,
+//!     4239805798134,
+//!     Dimension::DIM_END,
+//!     GeneratorFlags::empty(),
+//! );
+//!
+//! for spike in generator.end_spikes().expect("the generator is in the end") {
+//!     if spike.caged {
+//!         println!("Caged crystal on top of {:?} at y {}", spike.center, spike.height);
+//!     }
+//! }
+//! ```
+
+use crate::{
+    generator::{error::GeneratorError, BlockPosition, Generator},
+    rng::JavaRandom,
+};
+
+/// Amount of obsidian pillars around the main island
+pub const SPIKE_COUNT: usize = 10;
+
+/// An obsidian pillar around the main end island
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct EndSpike {
+    /// Position of the center of the pillar
+    pub center: BlockPosition,
+    /// Radius of the pillar in blocks
+    pub radius: i32,
+    /// The y coordinate of the top of the pillar
+    pub height: i32,
+    /// Whether the end crystal on top of the pillar is surrounded by an iron
+    /// bar cage
+    pub caged: bool,
+}
+
+impl Generator {
+    /// Gets the obsidian pillars around the main end island
+    ///
+    /// The pillars are ordered by their position on the ring around the exit
+    /// portal, starting from positive x and going clockwise.
+    pub fn end_spikes(&self) -> Result<[EndSpike; SPIKE_COUNT], GeneratorError> {
+        self.ensure_end()?;
+
+        Ok(end_spikes(self.seed()))
+    }
+}
+
+/// Calculates the pillars for a seed
+fn end_spikes(seed: i64) -> [EndSpike; SPIKE_COUNT] {
+    let spike_seed = JavaRandom::new(seed).next_long() & 0xffff;
+
+    let mut sizes: [i32; SPIKE_COUNT] = std::array::from_fn(|idx| idx as i32);
+
+    // Same as Collections.shuffle in java
+    let mut rng = JavaRandom::new(spike_seed);
+    for idx in (1..SPIKE_COUNT).rev() {
+        sizes.swap(idx, rng.next_int(idx as i32 + 1) as usize);
+    }
+
+    std::array::from_fn(|idx| {
+        let angle = 2.0 * (-std::f64::consts::PI + (std::f64::consts::PI / 10.0) * idx as f64);
+        let size = sizes[idx];
+
+        EndSpike {
+            center: BlockPosition::new(
+                (42.0 * angle.cos()).floor() as i32,
+                (42.0 * angle.sin()).floor() as i32,
+            ),
+            radius: 2 + size / 3,
+            height: 76 + size * 3,
+            caged: size == 1 || size == 2,
+        }
+    })
+}
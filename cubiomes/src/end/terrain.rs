@@ -0,0 +1,182 @@
+//! Module for the terrain of the end dimension
+//!
+//! The end consists of the main island, a ring of void around it and the outer
+//! islands starting roughly 1000 blocks from the center. This module samples
+//! the surface height of the islands and checks which chunks are empty void.
+//!
+//! The end uses its own terrain noise, so [`Generator::approx_surface_noise()`]
+//! can't be used for it. Use [`Generator::end_surface_heights()`] instead.
+//!
+//! # Examples
+//! ```
+//! use cubiomes::enums::{Dimension, MCVersion};
+//! use cubiomes::generator::{Generator, GeneratorFlags};
+//! use cubiomes::noise::SurfaceNoiseRelease;
+//!
+//! let seed = 4239805798134;
+//! let generator = Generator::new(
+//!     MCVersion::MC_1_21_WD,
+//!     seed,
+//!     Dimension::DIM_END,
+//!     GeneratorFlags::empty(),
+//! );
+//! let surface_noise = SurfaceNoiseRelease::new(Dimension::DIM_END, seed);
+//!
+//! // The main island is never empty
+//! assert!(!generator
+//!     .is_end_chunk_empty(0, 0, &surface_noise)
+//!     .expect("the generator is in the end"));
+//! ```
+
+use crate::{
+    generator::{
+        error::{GeneratorError, TryFromRangeError},
+        Generator, Scale,
+    },
+    noise::SurfaceNoiseRelease,
+};
+use cubiomes_sys::{isEndChunkEmpty, mapEndSurfaceHeight};
+
+impl Generator {
+    /// Generates a vector containing the surface height of the end islands
+    /// within the area.
+    ///
+    /// The area is given in [`Scale::Quad`], eg. locations map 1:4. Empty void
+    /// has a height of 0.
+    ///
+    /// The vector is indexed as follows: `[z * size_x + x]`, with x and z
+    /// relative to the top left corner of the area.
+    ///
+    /// The surface noise must be initialized for [`Dimension::DIM_END`] with
+    /// the seed of this generator.
+    ///
+    /// # Errors
+    /// Returns [`GeneratorError::WrongDimension`] if the generator isn't set
+    /// to the end, [`GeneratorError::TryFromRangeError`] if a size is bigger
+    /// than [`i32::MAX`], [`GeneratorError::AreaTooLarge`] if the heights of
+    /// the area can't be allocated, or
+    /// [`GeneratorError::SurfaceHeightFailure`] if cubiomes can't generate
+    /// the heights.
+    ///
+    /// [`Dimension::DIM_END`]: crate::enums::Dimension::DIM_END
+    pub fn end_surface_heights(
+        &self,
+        x: i32,
+        z: i32,
+        size_x: u32,
+        size_z: u32,
+        surface_noise: &SurfaceNoiseRelease,
+    ) -> Result<Vec<f32>, GeneratorError> {
+        self.ensure_end()?;
+
+        let capacity = area_len(size_x, size_z)?;
+        let mut buff = Vec::new();
+
+        buff.try_reserve_exact(capacity)
+            .map_err(|_| GeneratorError::AreaTooLarge { size_x, size_z })?;
+
+        // SAFETY: Foreign function is called with correct arguments
+        //
+        // buff can hold enough items to be filled, the generator is in the end
+        // so its end noise is initialized
+        let res = unsafe {
+            mapEndSurfaceHeight(
+                buff.as_mut_ptr(),
+                &(*self.as_ptr()).en,
+                surface_noise.as_ptr(),
+                x,
+                z,
+                size_x as i32,
+                size_z as i32,
+                Scale::Quad as i32,
+                0,
+            )
+        };
+
+        if res != 0 {
+            return Err(GeneratorError::SurfaceHeightFailure(res));
+        }
+
+        // SAFETY: as buff was filled with ffi it should hold capacity elements
+        unsafe {
+            buff.set_len(capacity);
+        }
+
+        Ok(buff)
+    }
+
+    /// Checks if the chunk at the given chunk coordinates is empty void
+    ///
+    /// A chunk is considered empty if it contains no terrain at all. This
+    /// matches the check the game uses when placing exit gateways, and is
+    /// useful for filtering out end cities in the void.
+    ///
+    /// The surface noise must be initialized for
+    /// [`crate::enums::Dimension::DIM_END`] with the seed of this generator.
+    pub fn is_end_chunk_empty(
+        &self,
+        chunk_x: i32,
+        chunk_z: i32,
+        surface_noise: &SurfaceNoiseRelease,
+    ) -> Result<bool, GeneratorError> {
+        self.ensure_end()?;
+
+        // SAFETY: The generator is in the end, so its end noise is initialized,
+        // and the surface noise is initialized for the end.
+        Ok(unsafe {
+            isEndChunkEmpty(
+                &(*self.as_ptr()).en,
+                surface_noise.as_ptr(),
+                self.seed() as u64,
+                chunk_x,
+                chunk_z,
+            ) != 0
+        })
+    }
+
+    /// Generates a heightmap of the end islands for the supplied area between
+    /// bottom and top
+    ///
+    /// This works like [`Self::generate_heightmap_image()`], but for the end
+    /// dimension. Position is given in [`Scale::Quad`], eg 1:4 scale.
+    ///
+    /// Black corresponds to `height <= bottom` and White `height >= top`. The
+    /// colors are mapped linearly in between the supplied values.
+    #[cfg(feature = "image")]
+    #[allow(clippy::too_many_arguments)]
+    pub fn generate_end_heightmap_image(
+        &self,
+        x: i32,
+        z: i32,
+        size_x: u32,
+        size_z: u32,
+        bottom: f32,
+        top: f32,
+        surface_noise: &SurfaceNoiseRelease,
+    ) -> Result<image::GrayImage, GeneratorError> {
+        use crate::generator::float_between;
+        use image::GrayImage;
+
+        let buf = self.end_surface_heights(x, z, size_x, size_z, surface_noise)?;
+
+        Ok(GrayImage::from_fn(size_x, size_z, |img_x, img_z| {
+            [float_between(
+                buf[img_z as usize * size_x as usize + img_x as usize],
+                bottom,
+                top,
+            )]
+            .into()
+        }))
+    }
+}
+
+/// Gets the amount of positions in an area, checking that the sizes fit the
+/// i32 cubiomes takes and the amount fits a usize
+fn area_len(size_x: u32, size_z: u32) -> Result<usize, GeneratorError> {
+    i32::try_from(size_x).map_err(|_| TryFromRangeError::XSizeOutOfBounds)?;
+    i32::try_from(size_z).map_err(|_| TryFromRangeError::ZSizeOutOfBounds)?;
+
+    (size_x as usize)
+        .checked_mul(size_z as usize)
+        .ok_or(GeneratorError::AreaTooLarge { size_x, size_z })
+}
@@ -0,0 +1,227 @@
+use crate::enums::{Dimension, MCVersion};
+use crate::generator::{
+    error::{GeneratorError, TryFromRangeError},
+    BlockPosition, Generator, GeneratorFlags,
+};
+use crate::noise::SurfaceNoiseRelease;
+
+const SEED: i64 = 4239805798134;
+
+fn end_generator() -> Generator {
+    Generator::new(
+        MCVersion::MC_1_21_WD,
+        SEED,
+        Dimension::DIM_END,
+        GeneratorFlags::empty(),
+    )
+}
+
+#[test]
+#[cfg_attr(miri, ignore)]
+fn gateways_form_a_ring() {
+    let mut positions = end_generator()
+        .end_gateway_positions()
+        .expect("the generator is in the end")
+        .to_vec();
+
+    positions.sort();
+
+    let mut expected = [
+        (96, 0),
+        (91, 29),
+        (77, 56),
+        (56, 77),
+        (29, 91),
+        (-1, 96),
+        (-30, 91),
+        (-57, 77),
+        (-78, 56),
+        (-92, 29),
+        (-96, -1),
+        (-92, -30),
+        (-78, -57),
+        (-57, -78),
+        (-30, -92),
+        (0, -96),
+        (29, -92),
+        (56, -78),
+        (77, -57),
+        (91, -30),
+    ]
+    .map(|(x, z)| BlockPosition::new(x, z));
+
+    expected.sort();
+
+    assert_eq!(positions, expected);
+}
+
+#[test]
+#[cfg_attr(miri, ignore)]
+fn gateway_order_depends_on_seed() {
+    let mut generator = end_generator();
+    let first = generator.end_gateway_positions();
+
+    generator.apply_seed(Dimension::DIM_END, SEED + 1);
+
+    assert_ne!(first, generator.end_gateway_positions());
+}
+
+#[test]
+#[cfg_attr(miri, ignore)]
+fn gateway_exits_are_on_outer_islands() {
+    let origin = BlockPosition::new(0, 0);
+
+    for gateway in end_generator()
+        .end_gateways()
+        .expect("the generator is in the end")
+    {
+        let distance = origin.distance_to(gateway.exit);
+
+        // The search starts 1024 blocks out and moves at most 16 chunks in
+        // either direction
+        assert!((767.0..=1281.0).contains(&distance));
+
+        // The exit is in the same direction as the gateway
+        let dot = gateway.position.x as i64 * gateway.exit.x as i64
+            + gateway.position.z as i64 * gateway.exit.z as i64;
+        assert!(dot > 0);
+    }
+}
+
+#[test]
+#[cfg_attr(miri, ignore)]
+fn gateways_require_the_end() {
+    let generator = Generator::new(
+        MCVersion::MC_1_21_WD,
+        SEED,
+        Dimension::DIM_OVERWORLD,
+        GeneratorFlags::empty(),
+    );
+
+    assert_eq!(
+        generator.end_gateways(),
+        Err(GeneratorError::WrongDimension {
+            expected: Dimension::DIM_END,
+            actual: Dimension::DIM_OVERWORLD
+        })
+    );
+}
+
+#[test]
+#[cfg_attr(miri, ignore)]
+fn main_island_is_surrounded_by_void() {
+    let generator = end_generator();
+    let surface_noise = SurfaceNoiseRelease::new(Dimension::DIM_END, SEED);
+
+    assert!(!generator
+        .is_end_chunk_empty(0, 0, &surface_noise)
+        .expect("the generator is in the end"));
+
+    // The ring of void between the main island and the outer islands
+    assert!(generator
+        .is_end_chunk_empty(40, 0, &surface_noise)
+        .expect("the generator is in the end"));
+}
+
+#[test]
+#[cfg_attr(miri, ignore)]
+fn end_surface_heights() {
+    let generator = end_generator();
+    let surface_noise = SurfaceNoiseRelease::new(Dimension::DIM_END, SEED);
+
+    // 64 by 64 blocks around the center of the main island
+    let heights = generator
+        .end_surface_heights(-8, -8, 16, 16, &surface_noise)
+        .expect("the generator is in the end");
+
+    assert_eq!(heights.len(), 16 * 16);
+    assert!(heights[8 * 16 + 8] > 0.0);
+
+    // A 16 by 16 block area inside the void ring
+    let heights = generator
+        .end_surface_heights(160, 0, 4, 4, &surface_noise)
+        .expect("the generator is in the end");
+
+    assert!(heights.iter().all(|height| *height == 0.0));
+}
+
+#[test]
+#[cfg_attr(miri, ignore)]
+fn end_surface_heights_rejects_oversized_areas() {
+    let generator = end_generator();
+    let surface_noise = SurfaceNoiseRelease::new(Dimension::DIM_END, SEED);
+    let too_big = i32::MAX as u32 + 1;
+
+    assert_eq!(
+        generator.end_surface_heights(0, 0, too_big, 1, &surface_noise),
+        Err(GeneratorError::TryFromRangeError(
+            TryFromRangeError::XSizeOutOfBounds
+        ))
+    );
+    assert_eq!(
+        generator.end_surface_heights(0, 0, 1, too_big, &surface_noise),
+        Err(GeneratorError::TryFromRangeError(
+            TryFromRangeError::ZSizeOutOfBounds
+        ))
+    );
+}
+
+#[test]
+#[cfg_attr(miri, ignore)]
+fn end_surface_heights_rejects_unallocatable_areas() {
+    let generator = end_generator();
+    let surface_noise = SurfaceNoiseRelease::new(Dimension::DIM_END, SEED);
+    let size = i32::MAX as u32;
+
+    // Both sizes fit an i32, but the heights would take more than isize::MAX
+    // bytes
+    assert_eq!(
+        generator.end_surface_heights(0, 0, size, size, &surface_noise),
+        Err(GeneratorError::AreaTooLarge {
+            size_x: size,
+            size_z: size,
+        })
+    );
+}
+
+#[test]
+#[cfg_attr(miri, ignore)]
+fn end_spike_layout() {
+    let spikes = end_generator()
+        .end_spikes()
+        .expect("the generator is in the end");
+
+    let centers = spikes.map(|spike| (spike.center.x, spike.center.z));
+    assert_eq!(
+        centers,
+        [
+            (42, 0),
+            (33, 24),
+            (12, 39),
+            (-13, 39),
+            (-34, 24),
+            (-42, -1),
+            (-34, -25),
+            (-13, -40),
+            (12, -40),
+            (33, -25),
+        ]
+    );
+
+    let mut heights = spikes.map(|spike| spike.height);
+    heights.sort();
+    assert_eq!(heights, [76, 79, 82, 85, 88, 91, 94, 97, 100, 103]);
+
+    let mut radii = spikes.map(|spike| spike.radius);
+    radii.sort();
+    assert_eq!(radii, [2, 2, 2, 3, 3, 3, 4, 4, 4, 5]);
+
+    // The two smallest pillars after the shortest one are caged
+    let mut caged: Vec<i32> = spikes
+        .iter()
+        .filter(|spike| spike.caged)
+        .map(|spike| spike.height)
+        .collect();
+    caged.sort();
+    assert_eq!(caged, [79, 82]);
+}
@@ -0,0 +1,336 @@
+//! Crate level error type
+//!
+//! Each module has its own error type describing what went wrong, but none of
+//! them know what was being generated when the error happened. [`Error`]
+//! collects all of them, and can carry an [`ErrorContext`] describing the
+//! inputs of the failed request.
+//!
+//! The functions of this crate return their module specific errors and never
+//! attach context themselves. Callers opt in by converting the error with
+//! [`ResultExt`] or [`Error::with_context()`]. The context is the message of
+//! [`Error::WithContext`], and the original error is its
+//! [`std::error::Error::source()`], so error reporters walking the chain show
+//! both.
+//!
+//! # Usage
+//! ```
+//! use cubiomes::enums::{Dimension, MCVersion};
+//! use cubiomes::error::{Error, ResultExt};
+//! use cubiomes::generator::{Cache, Generator, GeneratorFlags, Range, Scale};
+//!
+//! let generator = Generator::new(
+//!     MCVersion::MC_1_16_5,
+//!     -4172144997902289642,
+//!     Dimension::DIM_OVERWORLD,
+//!     GeneratorFlags::empty(),
+//! );
+//!
+//! // 1.16 can't generate 1:1024 directly
+//! let range = Range {
+//!     scale: Scale::DoubleRegion,
+//!     x: 0,
+//!     z: 0,
+//!     size_x: 16,
+//!     size_z: 16,
+//!     y: 16,
+//!     size_y: 0,
+//! };
+//!
+//! let err: Error = Cache::new(&generator, range)
+//!     .with_generator(&generator)
+//!     .with_range(range)
+//!     .expect_err("1.16 doesn't support 1:1024");
+//!
+//! assert_eq!(err.context().and_then(|context| context.seed), Some(-4172144997902289642));
+//!
+//! // Logs the seed, version, dimension and range, followed by the error itself
+//! println!("{err}");
+//!
+//! if let Some(source) = std::error::Error::source(&err) {
+//!     println!("caused by: {source}");
+//! }
+//! ```
+
+use std::fmt::Display;
+
+use cubiomes_sys::enums::{Dimension, MCVersion, ParseError};
+
+use crate::{
+    generator::{
+        error::{GeneratorError, TryFromRangeError},
+        Cache, Generator, Range,
+    },
+    noise::NoiseError,
+    seed::SeedParseError,
+    structures::StructureGenerationError,
+};
+
+/// Any error produced by this crate
+///
+/// All the module specific errors can be converted into this type with
+/// [`From`]. Use [`Error::with_context()`] or [`ResultExt`] to attach the
+/// inputs of the failed request.
+#[derive(thiserror::Error, Debug, Clone, PartialEq, Eq)]
+pub enum Error {
+    /// An error from biome generation, see [`GeneratorError`]
+    #[error(transparent)]
+    Generator(GeneratorError),
+    /// An invalid range, see [`TryFromRangeError`]
+    #[error(transparent)]
+    Range(TryFromRangeError),
+    /// An error from structure generation, see [`StructureGenerationError`]
+    #[error(transparent)]
+    Structure(StructureGenerationError),
+    /// An error parsing one of the enums in [`crate::enums`]
+    #[error(transparent)]
+    Parse(ParseError),
+    /// An error initializing noise, see [`NoiseError`]
+    #[error(transparent)]
+    Noise(NoiseError),
+    /// An error rendering a map, see [`crate::render::RenderError`]
+    #[cfg(feature = "image")]
+    #[error(transparent)]
+    Render(crate::render::RenderError),
+    /// An error parsing a seed, see [`SeedParseError`]
+    #[error(transparent)]
+    Seed(SeedParseError),
+    /// An error rendering tiles, see [`crate::tiles::TileError`]
+    #[cfg(feature = "tiles")]
+    #[error(transparent)]
+    Tiles(crate::tiles::TileError),
+    /// An error together with the inputs of the request which failed
+    ///
+    /// Only the context is displayed, the error is the source of this one.
+    #[error("request failed with {context}")]
+    WithContext {
+        /// The inputs of the failed request
+        context: ErrorContext,
+        /// The error which happened
+        source: Box<Error>,
+    },
+}
+
+impl Error {
+    /// Attaches context to the error
+    ///
+    /// If the error already has context, fields missing from the existing
+    /// context are filled in from the given one.
+    #[must_use]
+    pub fn with_context(self, context: ErrorContext) -> Self {
+        match self {
+            Error::WithContext {
+                context: existing,
+                source,
+            } => Error::WithContext {
+                context: existing.or(context),
+                source,
+            },
+            err => Error::WithContext {
+                context,
+                source: Box::new(err),
+            },
+        }
+    }
+
+    /// Gets the context attached to the error, if any
+    #[must_use]
+    pub fn context(&self) -> Option<&ErrorContext> {
+        match self {
+            Error::WithContext { context, .. } => Some(context),
+            _ => None,
+        }
+    }
+
+    /// Gets the error without any context attached
+    #[must_use]
+    pub fn without_context(&self) -> &Error {
+        match self {
+            Error::WithContext { source, .. } => source.without_context(),
+            err => err,
+        }
+    }
+}
+
+impl From<GeneratorError> for Error {
+    fn from(value: GeneratorError) -> Self {
+        Self::Generator(value)
+    }
+}
+
+impl From<TryFromRangeError> for Error {
+    fn from(value: TryFromRangeError) -> Self {
+        Self::Range(value)
+    }
+}
+
+impl From<StructureGenerationError> for Error {
+    fn from(value: StructureGenerationError) -> Self {
+        Self::Structure(value)
+    }
+}
+
+impl From<ParseError> for Error {
+    fn from(value: ParseError) -> Self {
+        Self::Parse(value)
+    }
+}
+
+impl From<NoiseError> for Error {
+    fn from(value: NoiseError) -> Self {
+        Self::Noise(value)
+    }
+}
+
+#[cfg(feature = "image")]
+impl From<crate::render::RenderError> for Error {
+    fn from(value: crate::render::RenderError) -> Self {
+        Self::Render(value)
+    }
+}
+
+impl From<SeedParseError> for Error {
+    fn from(value: SeedParseError) -> Self {
+        Self::Seed(value)
+    }
+}
+
+#[cfg(feature = "tiles")]
+impl From<crate::tiles::TileError> for Error {
+    fn from(value: crate::tiles::TileError) -> Self {
+        Self::Tiles(value)
+    }
+}
+
+/// The inputs of a request which failed
+///
+/// Every field is optional, as not every request has all of them.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub struct ErrorContext {
+    /// The seed of the generator
+    pub seed: Option<i64>,
+    /// The minecraft version of the generator
+    pub version: Option<MCVersion>,
+    /// The dimension of the generator
+    pub dimension: Option<Dimension>,
+    /// The range being generated
+    pub range: Option<Range>,
+}
+
+impl ErrorContext {
+    /// Sets the range of the context
+    #[must_use]
+    pub fn with_range(self, range: Range) -> Self {
+        Self {
+            range: Some(range),
+            ..self
+        }
+    }
+
+    /// Fills the fields missing from self from `other`
+    #[must_use]
+    pub fn or(self, other: Self) -> Self {
+        Self {
+            seed: self.seed.or(other.seed),
+            version: self.version.or(other.version),
+            dimension: self.dimension.or(other.dimension),
+            range: self.range.or(other.range),
+        }
+    }
+}
+
+impl From<&Generator> for ErrorContext {
+    fn from(generator: &Generator) -> Self {
+        Self {
+            seed: Some(generator.seed()),
+            version: generator.minecraft_version().ok(),
+            dimension: generator.dimension().ok(),
+            range: None,
+        }
+    }
+}
+
+impl From<&Cache<'_>> for ErrorContext {
+    fn from(cache: &Cache<'_>) -> Self {
+        ErrorContext::from(cache.generator()).with_range(*cache.range())
+    }
+}
+
+impl Display for ErrorContext {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let mut separator = "";
+
+        if let Some(seed) = self.seed {
+            write!(f, "seed {seed}")?;
+            separator = ", ";
+        }
+
+        if let Some(version) = self.version {
+            write!(f, "{separator}version {version}")?;
+            separator = ", ";
+        }
+
+        if let Some(dimension) = self.dimension {
+            write!(f, "{separator}dimension {dimension}")?;
+            separator = ", ";
+        }
+
+        if let Some(range) = self.range {
+            write!(
+                f,
+                "{separator}range 1:{} at x {} z {} y {} of size {}x{}x{}",
+                range.scale as i32,
+                range.x,
+                range.z,
+                range.y,
+                range.size_x,
+                range.size_z,
+                range.size_y.max(1),
+            )?;
+            separator = ", ";
+        }
+
+        if separator.is_empty() {
+            write!(f, "no context")?;
+        }
+
+        Ok(())
+    }
+}
+
+/// Extension trait for attaching context to results
+///
+/// Implemented for every result whose error converts into [`Error`].
+pub trait ResultExt<T> {
+    /// Converts the error and attaches the given context to it
+    ///
+    /// # Errors
+    /// Returns the error of self with the context attached
+    fn with_context(self, context: ErrorContext) -> Result<T, Error>;
+
+    /// Converts the error and attaches the seed, version and dimension of the
+    /// generator to it
+    ///
+    /// # Errors
+    /// Returns the error of self with the context attached
+    fn with_generator(self, generator: &Generator) -> Result<T, Error>;
+
+    /// Converts the error and attaches the range to it
+    ///
+    /// # Errors
+    /// Returns the error of self with the context attached
+    fn with_range(self, range: Range) -> Result<T, Error>;
+}
+
+impl<T, E: Into<Error>> ResultExt<T> for Result<T, E> {
+    fn with_context(self, context: ErrorContext) -> Result<T, Error> {
+        self.map_err(|err| err.into().with_context(context))
+    }
+
+    fn with_generator(self, generator: &Generator) -> Result<T, Error> {
+        self.with_context(ErrorContext::from(generator))
+    }
+
+    fn with_range(self, range: Range) -> Result<T, Error> {
+        self.with_context(ErrorContext::default().with_range(range))
+    }
+}
@@ -1,5 +1,7 @@
 //! Errors related to [`super::Generator`] and [`super::Range`]
 
+use super::Scale;
+use cubiomes_sys::enums::{Dimension, MCVersion};
 use thiserror::Error;
 
 /// An error with the generator
@@ -32,6 +34,12 @@ pub enum GeneratorError {
     /// non 0 exit code.
     #[error("Function genBiomes failed with error code {0}")]
     GenBiomeToCacheFailure(i32),
+    /// Failed to generate a layer
+    ///
+    /// This indicates, that cubiomes failed to generate the area for a layer
+    /// and returned a non 0 exit code.
+    #[error("Function genArea failed with error code {0}")]
+    GenLayerFailure(i32),
     /// Index out of bounds while getting from the cache
     ///
     /// This indicates that [`super::Cache::biome_at()`] tried to get an index
@@ -40,9 +48,96 @@ pub enum GeneratorError {
     /// of the lenght of the vector
     #[error("Index out of bounds")]
     IndexOutOfBounds,
+    /// Failed to generate surface heights
+    ///
+    /// This indicates that cubiomes failed to generate the surface heights for
+    /// the area and returned a non 0 exit code.
+    #[error("Surface height generation failed with error code {0}")]
+    SurfaceHeightFailure(i32),
     #[error("Failed to convert range")]
     /// An error happened converting the range for use with cubiomes.
     TryFromRangeError(TryFromRangeError),
+    /// The generator has the wrong dimension applied
+    ///
+    /// Some functionality only makes sense in a specific dimension, for example
+    /// end gateways. Use [`super::Generator::apply_seed()`] to change the
+    /// dimension of the generator.
+    #[error("Generator is set to {actual}, but this requires {expected}")]
+    WrongDimension {
+        /// The dimension required for the action
+        expected: Dimension,
+        /// The dimension the generator is set to
+        actual: Dimension,
+    },
+    /// The generator can't generate biomes for its dimension in its version
+    ///
+    /// For example the end did not exist in beta versions of minecraft.
+    #[error("Generating biomes for {dimension} is not supported in {version}")]
+    UnsupportedDimension {
+        /// The dimension of the generator
+        dimension: Dimension,
+        /// The minecraft version of the generator
+        version: MCVersion,
+    },
+    /// The generator can't generate biomes at the scale of the range
+    ///
+    /// For example the layered generator used before 1.18 only generates
+    /// scales up to [`Scale::HalfRegion`]. Coarser maps can still be made with
+    /// [`super::Cache::new_downsampled()`].
+    #[error("Generating {dimension} at 1:{} is not supported in {version}", *scale as i32)]
+    UnsupportedScale {
+        /// The dimension of the generator
+        dimension: Dimension,
+        /// The minecraft version of the generator
+        version: MCVersion,
+        /// The requested scale
+        scale: Scale,
+    },
+    /// The scales given for downsampling don't work together
+    ///
+    /// Downsampling requires the source scale to be finer than the scale of
+    /// the range, and the range to be a plane.
+    #[error("Can't downsample from 1:{} to 1:{}", *from as i32, *to as i32)]
+    InvalidDownsample {
+        /// The scale the biomes are generated at
+        from: Scale,
+        /// The scale of the range
+        to: Scale,
+    },
+    /// The generator has a minecraft version which is not a valid [`MCVersion`]
+    ///
+    /// This can only happen if the raw generator has been modified through
+    /// [`super::Generator::as_mut_ptr()`].
+    #[error("Generator has an invalid minecraft version {0}")]
+    InvalidMinecraftVersion(i32),
+    /// The generator has a dimension which is not a valid [`Dimension`]
+    ///
+    /// This can only happen if the raw generator has been modified through
+    /// [`super::Generator::as_mut_ptr()`].
+    #[error("Generator has an invalid dimension {0}")]
+    InvalidDimension(i32),
+    /// The surface noise given doesn't match the minecraft version
+    ///
+    /// Beta 1.7 and older need [`crate::noise::BiomeNoise::Beta`], later
+    /// versions need [`crate::noise::BiomeNoise::Release`].
+    #[error("Surface noise variant does not match minecraft version {0}")]
+    WrongSurfaceNoise(MCVersion),
+    /// The minecraft version of the generator does not support the action
+    ///
+    /// For example end gateways were only added in 1.9.
+    #[error("Minecraft version {0} does not support this")]
+    UnsupportedVersion(MCVersion),
+    /// The values of the area don't fit in memory
+    ///
+    /// The sizes fit the i32 cubiomes takes, but their product is too large
+    /// to allocate.
+    #[error("Area of {size_x}x{size_z} is too large to allocate")]
+    AreaTooLarge {
+        /// The x size of the area
+        size_x: u32,
+        /// The z size of the area
+        size_z: u32,
+    },
 }
 
 impl From<TryFromRangeError> for GeneratorError {
@@ -0,0 +1,212 @@
+//! Typed heightmaps
+
+use std::io::{self, Write};
+
+use super::{error::GeneratorError, Generator, Scale};
+use crate::noise::BiomeNoise;
+
+/// Approximate surface heights of an area
+///
+/// The area is given in [`Scale::Quad`], eg. each height covers 4x4 blocks.
+/// The heights are stored row by row from north to south, with x changing
+/// fastest, like in a [`super::Cache`].
+#[derive(Debug, Clone, PartialEq)]
+pub struct Heightmap {
+    x: i32,
+    z: i32,
+    size_x: u32,
+    size_z: u32,
+    heights: Vec<f32>,
+}
+
+impl Heightmap {
+    /// The scale of the heightmap
+    pub const SCALE: Scale = Scale::Quad;
+
+    /// Creates a new heightmap from heights in [`Scale::Quad`]
+    ///
+    /// Returns [`None`] if the amount of heights doesn't match the size.
+    #[must_use]
+    pub fn new(x: i32, z: i32, size_x: u32, size_z: u32, heights: Vec<f32>) -> Option<Self> {
+        if heights.len() != size_x as usize * size_z as usize {
+            return None;
+        }
+
+        Some(Self {
+            x,
+            z,
+            size_x,
+            size_z,
+            heights,
+        })
+    }
+
+    /// Gets the x coordinate of the north west corner in [`Scale::Quad`]
+    #[must_use]
+    pub fn x(&self) -> i32 {
+        self.x
+    }
+
+    /// Gets the z coordinate of the north west corner in [`Scale::Quad`]
+    #[must_use]
+    pub fn z(&self) -> i32 {
+        self.z
+    }
+
+    /// Gets the amount of heights along x
+    #[must_use]
+    pub fn size_x(&self) -> u32 {
+        self.size_x
+    }
+
+    /// Gets the amount of heights along z
+    #[must_use]
+    pub fn size_z(&self) -> u32 {
+        self.size_z
+    }
+
+    /// Gets all heights, indexed as `[z * size_x + x]`
+    #[must_use]
+    pub fn heights(&self) -> &[f32] {
+        &self.heights
+    }
+
+    /// Consumes the heightmap and returns the heights
+    #[must_use]
+    pub fn into_heights(self) -> Vec<f32> {
+        self.heights
+    }
+
+    /// Gets the height at coordinates relative to the north west corner, or
+    /// [`None`] if they are outside the heightmap
+    #[must_use]
+    pub fn get(&self, x: u32, z: u32) -> Option<f32> {
+        if x >= self.size_x || z >= self.size_z {
+            return None;
+        }
+
+        self.heights
+            .get(z as usize * self.size_x as usize + x as usize)
+            .copied()
+    }
+
+    /// Gets the height at a block position, or [`None`] if it is outside the
+    /// heightmap
+    #[must_use]
+    pub fn height_at(&self, block_x: i32, block_z: i32) -> Option<f32> {
+        let scale = Self::SCALE as i32;
+
+        let x = u32::try_from(block_x.div_euclid(scale).checked_sub(self.x)?).ok()?;
+        let z = u32::try_from(block_z.div_euclid(scale).checked_sub(self.z)?).ok()?;
+
+        self.get(x, z)
+    }
+
+    /// Gets the lowest and highest height, or [`None`] if the heightmap is
+    /// empty
+    #[must_use]
+    pub fn min_max(&self) -> Option<(f32, f32)> {
+        self.heights
+            .iter()
+            .fold(None, |min_max, height| match min_max {
+                None => Some((*height, *height)),
+                Some((min, max)) => Some((min.min(*height), max.max(*height))),
+            })
+    }
+
+    /// Writes the heights as raw little endian 32 bit floats
+    ///
+    /// The heights are written row by row from north to south, with x changing
+    /// fastest. This is the layout most GIS tools expect from a raw raster.
+    ///
+    /// # Errors
+    /// Returns any error from the writer
+    pub fn write_raw<W: Write>(&self, mut writer: W) -> io::Result<()> {
+        for height in &self.heights {
+            writer.write_all(&height.to_le_bytes())?;
+        }
+
+        writer.flush()
+    }
+
+    /// Generates an 8 bit grayscale image of the heights between bottom and
+    /// top
+    ///
+    /// This function requires crate feature image
+    ///
+    /// Black corresponds to `height <= bottom` and White `height >= top`. The
+    /// colors are mapped linearly in between the supplied values.
+    #[cfg(feature = "image")]
+    #[must_use]
+    pub fn to_gray_image(&self, bottom: f32, top: f32) -> image::GrayImage {
+        use super::float_between;
+
+        image::GrayImage::from_fn(self.size_x, self.size_z, |x, z| {
+            [float_between(
+                self.heights[z as usize * self.size_x as usize + x as usize],
+                bottom,
+                top,
+            )]
+            .into()
+        })
+    }
+
+    /// Generates a 16 bit grayscale image of the heights between bottom and
+    /// top
+    ///
+    /// This function requires crate feature image
+    ///
+    /// Works like [`Self::to_gray_image()`], but with 65536 levels instead of
+    /// 256, which avoids banding. The image can be saved as a 16 bit PNG.
+    ///
+    /// # Examples
+    /// ```
+    #[doc = include_str!("../../examples/export_heightmap.rs")]
+    /// ```
+    #[cfg(feature = "image")]
+    #[must_use]
+    pub fn to_gray16_image(
+        &self,
+        bottom: f32,
+        top: f32,
+    ) -> image::ImageBuffer<image::Luma<u16>, Vec<u16>> {
+        let scale = u16::MAX as f32 / (top - bottom);
+
+        image::ImageBuffer::from_fn(self.size_x, self.size_z, |x, z| {
+            let height = self.heights[z as usize * self.size_x as usize + x as usize];
+
+            [((height - bottom) * scale)
+                .clamp(0.0, u16::MAX as f32)
+                .round() as u16]
+            .into()
+        })
+    }
+}
+
+impl Generator {
+    /// Generates a heightmap of approximate surface heights within the area
+    ///
+    /// The area is given in [`Scale::Quad`], eg. locations map 1:4. See
+    /// [`Self::approx_surface_noise()`] for which noise to use.
+    ///
+    /// # Errors
+    /// Returns the same errors as [`Self::approx_surface_noise()`]
+    pub fn heightmap(
+        &self,
+        x: i32,
+        z: i32,
+        size_x: u32,
+        size_z: u32,
+        surface_noise: &BiomeNoise,
+    ) -> Result<Heightmap, GeneratorError> {
+        let heights = self.approx_surface_noise(x, z, size_x, size_z, surface_noise)?;
+
+        Ok(Heightmap {
+            x,
+            z,
+            size_x,
+            size_z,
+            heights,
+        })
+    }
+}
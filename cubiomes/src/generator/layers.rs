@@ -0,0 +1,276 @@
+use super::{
+    error::{GeneratorError, TryFromRangeError},
+    Generator,
+};
+use crate::enums::{BiomeID, Dimension, MCVersion};
+use cubiomes_sys::{genArea, getMinLayerCacheSize, num_traits::FromPrimitive, LayerId};
+
+/// A named layer of the layered biome generator
+///
+/// Minecraft 1.0 through 1.17 generate overworld biomes by running a stack of
+/// layers, each one refining the output of the previous ones. The coarse
+/// layers are much faster to generate than the final biomes, so they are
+/// useful for filtering out seeds before doing more expensive checks.
+///
+/// The scale listed for each layer is the default one. Large biomes and
+/// other generator flags can change it, the actual scale is reported by
+/// [`LayerArea::scale`].
+///
+/// Not every layer exists in every version, generating a layer which is not
+/// part of the layer stack of the generator returns an error.
+#[derive(Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Debug, Hash)]
+pub enum Layer {
+    /// Land and ocean at 1:4096, land is 1 and ocean is 0
+    Continent,
+    /// Temperature categories at 1:1024, before any cooling or heating
+    Snow,
+    /// Temperature categories at 1:1024, with warm areas cooled next to cold
+    /// ones
+    Cool,
+    /// Temperature categories at 1:1024, with cold areas heated next to warm
+    /// ones
+    Heat,
+    /// Temperature categories at 1:1024, with special biome variants marked
+    ///
+    /// Only exists since 1.7
+    Special,
+    /// Biome categories at 1:256, with mushroom islands placed
+    Mushroom,
+    /// Biome categories at 1:256, with deep oceans placed
+    ///
+    /// Only exists since 1.7
+    DeepOcean,
+    /// Biomes at 1:256, the first layer which produces actual biomes
+    Biome,
+    /// Biomes at 1:256, with bamboo jungles placed
+    ///
+    /// Only exists since 1.14
+    Bamboo,
+    /// Biomes at 1:64, with edge biomes placed between incompatible biomes
+    BiomeEdge,
+    /// Noise at 1:256 used as the input for both hills and rivers
+    RiverInit,
+    /// Biomes at 1:64, with hills and mutated variants placed
+    Hills,
+    /// Biomes at 1:64, with sunflower plains placed
+    ///
+    /// Only exists since 1.7
+    Sunflower,
+    /// Biomes at 1:16, with beaches and other shores placed
+    Shore,
+    /// Biomes at 1:16, with rivers removed from some swamps and jungles
+    ///
+    /// Only exists before 1.7
+    SwampRiver,
+    /// Biomes at 1:4, before rivers are mixed in
+    Smooth,
+    /// The river mask at 1:4, rivers are river and everything else is -1
+    River,
+    /// Biomes at 1:4, with rivers mixed in
+    RiverMix,
+    /// Ocean temperature at 1:256
+    ///
+    /// Only exists since 1.13
+    OceanTemperature,
+    /// Biomes at 1:4, with ocean temperatures mixed in
+    ///
+    /// Only exists since 1.13
+    OceanMix,
+    /// Final biomes at 1:1
+    Voronoi,
+}
+
+impl Layer {
+    fn id(self) -> LayerId {
+        match self {
+            Layer::Continent => LayerId::L_CONTINENT_4096,
+            Layer::Snow => LayerId::L_SNOW_1024,
+            Layer::Cool => LayerId::L_COOL_1024,
+            Layer::Heat => LayerId::L_HEAT_1024,
+            Layer::Special => LayerId::L_SPECIAL_1024,
+            Layer::Mushroom => LayerId::L_MUSHROOM_256,
+            Layer::DeepOcean => LayerId::L_DEEP_OCEAN_256,
+            Layer::Biome => LayerId::L_BIOME_256,
+            Layer::Bamboo => LayerId::L_BAMBOO_256,
+            Layer::BiomeEdge => LayerId::L_BIOME_EDGE_64,
+            Layer::RiverInit => LayerId::L_RIVER_INIT_256,
+            Layer::Hills => LayerId::L_HILLS_64,
+            Layer::Sunflower => LayerId::L_SUNFLOWER_64,
+            Layer::Shore => LayerId::L_SHORE_16,
+            Layer::SwampRiver => LayerId::L_SWAMP_RIVER_16,
+            Layer::Smooth => LayerId::L_SMOOTH_4,
+            Layer::River => LayerId::L_RIVER_4,
+            Layer::RiverMix => LayerId::L_RIVER_MIX_4,
+            Layer::OceanTemperature => LayerId::L_OCEAN_TEMP_256,
+            Layer::OceanMix => LayerId::L_OCEAN_MIX_4,
+            Layer::Voronoi => LayerId::L_VORONOI_1,
+        }
+    }
+}
+
+/// The raw output of a single [`Layer`]
+///
+/// The values are indexed as `[z * size_x + x]`, with x and z relative to the
+/// top left corner of the area. Depending on the layer the values are either
+/// biome ids or layer specific values, see the documentation of [`Layer`].
+#[derive(Clone, PartialEq, Eq, Debug, Hash)]
+pub struct LayerArea {
+    /// The scale of the layer, one value covers `scale` x `scale` blocks
+    pub scale: u32,
+    /// Position of the top left corner of the area, scaled by [`Self::scale`]
+    pub x: i32,
+    /// Position of the top left corner of the area, scaled by [`Self::scale`]
+    pub z: i32,
+    /// Size of the area in x coordinates
+    pub size_x: u32,
+    /// Size of the area in z coordinates
+    pub size_z: u32,
+    /// The generated values
+    pub values: Vec<i32>,
+}
+
+impl LayerArea {
+    /// Gets the raw value at a position scaled by [`Self::scale`]
+    ///
+    /// Returns None if the position is outside of the area
+    #[must_use]
+    pub fn value_at(&self, x: i32, z: i32) -> Option<i32> {
+        let rel_x = usize::try_from(i64::from(x) - i64::from(self.x)).ok()?;
+        let rel_z = usize::try_from(i64::from(z) - i64::from(self.z)).ok()?;
+
+        if rel_x >= self.size_x as usize || rel_z >= self.size_z as usize {
+            return None;
+        }
+
+        self.values
+            .get(rel_z * self.size_x as usize + rel_x)
+            .copied()
+    }
+
+    /// Gets the value at a position scaled by [`Self::scale`] as a biome
+    ///
+    /// This only makes sense for layers which produce biomes.
+    ///
+    /// # Errors
+    /// Returns [`GeneratorError::IndexOutOfBounds`] if the position is outside
+    /// of the area and [`GeneratorError::BiomeIDOutOfRange`] if the value is
+    /// not a biome.
+    pub fn biome_at(&self, x: i32, z: i32) -> Result<BiomeID, GeneratorError> {
+        let value = self
+            .value_at(x, z)
+            .ok_or(GeneratorError::IndexOutOfBounds)?;
+
+        BiomeID::from_i32(value).ok_or(GeneratorError::BiomeIDOutOfRange(value))
+    }
+}
+
+impl Generator {
+    /// Generates an area from a single layer of the layered biome generator
+    ///
+    /// The position and size of the area are given in the coordinates of the
+    /// layer, eg. at 1:256 for [`Layer::OceanTemperature`]. The scale of the
+    /// layer is included in the returned [`LayerArea`].
+    ///
+    /// Layers only exist for the overworld from 1.0 up to and including 1.17.
+    ///
+    /// # Errors
+    /// Returns [`GeneratorError::WrongDimension`] if the generator isn't set to
+    /// the overworld and [`GeneratorError::UnsupportedVersion`] if the version
+    /// doesn't generate biomes with layers, or doesn't have the requested
+    /// layer.
+    ///
+    /// Returns [`GeneratorError::TryFromRangeError`] if either size is zero or
+    /// doesn't fit an i32 and [`GeneratorError::GenLayerFailure`] if cubiomes
+    /// fails to generate the layer.
+    ///
+    /// # Examples
+    /// ```
+    /// use cubiomes::enums::{Dimension, MCVersion};
+    /// use cubiomes::generator::{Generator, GeneratorFlags, Layer};
+    ///
+    /// let generator = Generator::new(
+    ///     MCVersion::MC_1_16_5,
+    ///     -4172144997902289642,
+    ///     Dimension::DIM_OVERWORLD,
+    ///     GeneratorFlags::empty(),
+    /// );
+    ///
+    /// // Check the land around spawn before generating any biomes
+    /// let continents = generator
+    ///     .generate_layer(Layer::Continent, -2, -2, 4, 4)
+    ///     .expect("1.16 generates biomes with layers");
+    ///
+    /// assert_eq!(continents.scale, 4096);
+    /// let land = continents.values.iter().filter(|value| **value != 0).count();
+    /// println!("{land} of 16 tiles have land");
+    /// ```
+    pub fn generate_layer(
+        &self,
+        layer: Layer,
+        x: i32,
+        z: i32,
+        size_x: u32,
+        size_z: u32,
+    ) -> Result<LayerArea, GeneratorError> {
+        let dimension = self.dimension()?;
+        if dimension != Dimension::DIM_OVERWORLD {
+            return Err(GeneratorError::WrongDimension {
+                expected: Dimension::DIM_OVERWORLD,
+                actual: dimension,
+            });
+        }
+
+        let version = self.minecraft_version()?;
+        if !(MCVersion::MC_1_0_0..=MCVersion::MC_1_17_1).contains(&version) {
+            return Err(GeneratorError::UnsupportedVersion(version));
+        }
+
+        let raw_size_x = i32::try_from(size_x)
+            .ok()
+            .filter(|size| *size > 0)
+            .ok_or(TryFromRangeError::XSizeOutOfBounds)?;
+        let raw_size_z = i32::try_from(size_z)
+            .ok()
+            .filter(|size| *size > 0)
+            .ok_or(TryFromRangeError::ZSizeOutOfBounds)?;
+
+        // SAFETY: The generator is initialized and uses the layer stack, as
+        // checked by the version above. The index is one of the layer ids,
+        // which are all smaller than the amount of layers in the stack.
+        let raw_layer = unsafe {
+            &(*self.as_ptr()).__bindgen_anon_1.__bindgen_anon_1.ls.layers[layer.id().0 as usize]
+        };
+
+        if raw_layer.getMap.is_none() {
+            return Err(GeneratorError::UnsupportedVersion(version));
+        }
+
+        // SAFETY: The layer is part of an initialized layer stack and the
+        // sizes are positive
+        let capacity = unsafe { getMinLayerCacheSize(raw_layer, raw_size_x, raw_size_z) };
+        let mut values: Vec<i32> = Vec::with_capacity(capacity);
+
+        // SAFETY: The buffer has the capacity cubiomes requires for the area
+        // and the layer has been seeded along with the generator
+        let res = unsafe { genArea(raw_layer, values.as_mut_ptr(), x, z, raw_size_x, raw_size_z) };
+
+        if res != 0 {
+            return Err(GeneratorError::GenLayerFailure(res));
+        }
+
+        // SAFETY: genArea filled the first size_x * size_z values of the
+        // buffer, which fits in the capacity
+        unsafe {
+            values.set_len(size_x as usize * size_z as usize);
+        }
+
+        Ok(LayerArea {
+            scale: raw_layer.scale as u32,
+            x,
+            z,
+            size_x,
+            size_z,
+            values,
+        })
+    }
+}
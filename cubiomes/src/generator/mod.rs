@@ -13,6 +13,9 @@
 //!
 //! For structure generation, see [`crate::structures`]
 //!
+//! For the individual layers of pre 1.18 biome generation, see
+//! [`Generator::generate_layer()`]
+//!
 //! ## Optimal height
 //!
 //! For the y value in generation you should generally use minecraft build limit
@@ -26,10 +29,16 @@
 //! This module follow closely to how the underlying cubiomes library works, but
 //! the features have been wrapped by a safe rust api
 
+pub use heightmap::*;
+pub use layers::*;
 pub use position::*;
 pub use range::*;
 
-use crate::{enums, noise::BiomeNoise};
+use crate::{
+    enums,
+    noise::{BiomeNoise, SurfaceNoiseBeta},
+    seed::WorldSeed,
+};
 use bitflags::bitflags;
 use cubiomes_sys::{
     enums::{Dimension, MCVersion},
@@ -37,13 +46,11 @@ use cubiomes_sys::{
     num_traits::FromPrimitive,
 };
 use error::GeneratorError;
-use std::{
-    alloc::{alloc, dealloc, Layout},
-    fmt::Debug,
-    mem::transmute,
-};
+use std::{cmp::Reverse, collections::BTreeMap, fmt::Debug, mem::transmute};
 
 pub mod error;
+mod heightmap;
+mod layers;
 mod position;
 mod range;
 
@@ -79,25 +86,30 @@ bitflags! {
 ///
 /// Biomes can be generated either with [`Self::get_biome_at()`] for single
 /// points, or in conjuntion with a [`Cache`] generated by [`Cache::new()`]
-#[derive(Debug)]
+///
+/// The cubiomes generator is stored in a box, as it contains pointers into
+/// itself and such can't be moved after it has been set up.
 pub struct Generator {
-    generator: *mut cubiomes_sys::Generator,
+    generator: Box<cubiomes_sys::Generator>,
 }
 
-impl Drop for Generator {
-    fn drop(&mut self) {
-        // Safety:
-        // The memory is safe to deallocate as its been allocated in new
-        // and the pointer to it is dropped, so it is never referred to again
-        unsafe {
-            dealloc(self.generator as *mut u8, Layout::new::<Generator>());
-        }
+// The cubiomes generator has no Debug implementation, as it contains unions.
+// Only the inputs the generator was set up with are shown.
+impl Debug for Generator {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("Generator")
+            .field("mc", &self.generator.mc)
+            .field("dim", &self.generator.dim)
+            .field("seed", &self.seed())
+            .field("flags", &self.generator.flags)
+            .finish_non_exhaustive()
     }
 }
 
-// SAFETY: As the raw pointer inside generator is exclusive to this instance of
-// generator, sending it in between threads should be safe. Only way to access
-// the raw pointer is via unsafe.
+// SAFETY: As the boxed generator is exclusive to this instance of generator,
+// sending it in between threads should be safe. The pointers inside it only
+// point into the box itself. Only way to access the raw generator is via
+// unsafe.
 unsafe impl Send for Generator {}
 
 // SAFETY: The generator shouldn't have interior mutation without an exclusive
@@ -105,6 +117,36 @@ unsafe impl Send for Generator {}
 // (even briefly) should require an exclusive reference.
 unsafe impl Sync for Generator {}
 
+impl Clone for Generator {
+    /// Creates a new generator with the same version, flags, seed and
+    /// dimension
+    ///
+    /// The cubiomes generator contains pointers into itself, so it can't be
+    /// copied byte for byte. Instead the clone is set up and seeded from
+    /// scratch, which gives an identical generator.
+    fn clone(&self) -> Self {
+        let mc = self.generator.mc;
+        let dim = self.generator.dim;
+        let flags = self.generator.flags;
+        let seed = self.generator.seed;
+
+        let mut generator = Self::zeroed();
+
+        // SAFETY:
+        // setupGenerator initializes the zeroed generator with the same
+        // inputs the original was set up with.
+        unsafe { cubiomes_sys::setupGenerator(generator.as_mut_ptr(), mc, flags) };
+
+        // A generator without a seed has an undefined dimension
+        if dim != Dimension::DIM_UNDEF as i32 {
+            // SAFETY: The generator was initialized above
+            unsafe { cubiomes_sys::applySeed(generator.as_mut_ptr(), dim, seed) };
+        }
+
+        generator
+    }
+}
+
 impl Generator {
     /// Initializes a new generator for the given minecraft version and flags
     /// with a seed and dimension applied
@@ -125,10 +167,24 @@ impl Generator {
     ///
     /// // Use the generator for something
     /// ```
+    ///
+    /// Seeds can also be parsed from text like in game, see
+    /// [`WorldSeed::parse()`]:
+    /// ```
+    /// use cubiomes::generator::{Generator, GeneratorFlags};
+    /// use cubiomes::enums::{MCVersion, Dimension};
+    /// use cubiomes::seed::WorldSeed;
+    ///
+    /// let seed: WorldSeed = "glacier".parse().expect("the seed isn't empty");
+    ///
+    /// let generator = Generator::new(MCVersion::MC_1_21_WD, seed, Dimension::DIM_OVERWORLD, GeneratorFlags::empty());
+    ///
+    /// assert_eq!(generator.seed(), 108181935);
+    /// ```
     #[must_use]
     pub fn new(
         mc_version: MCVersion,
-        seed: i64,
+        seed: impl Into<WorldSeed>,
         dimension: enums::Dimension,
         flags: GeneratorFlags,
     ) -> Self {
@@ -169,17 +225,32 @@ impl Generator {
     /// ```
     #[must_use]
     pub unsafe fn new_without_seed(version: MCVersion, flags: GeneratorFlags) -> Self {
+        let mut generator = Self::zeroed();
+
         // SAFETY:
-        // The function is safe since the generated pointer
-        // points to memory that can fit a Generator and
-        // the pointer is stored as a pointer
+        // setupGenerator initializes the zeroed generator for the version
         unsafe {
-            let generator =
-                alloc(Layout::new::<cubiomes_sys::Generator>()) as *mut cubiomes_sys::Generator;
-
-            cubiomes_sys::setupGenerator(generator, version as i32, flags.bits());
-            Self { generator }
+            cubiomes_sys::setupGenerator(generator.as_mut_ptr(), version as i32, flags.bits());
         }
+
+        generator
+    }
+
+    /// Allocates a generator with every field set to zero
+    ///
+    /// The generator must be set up with `setupGenerator` before it is used
+    /// for anything else than dropping it.
+    fn zeroed() -> Self {
+        // SAFETY:
+        // The box has the layout of the cubiomes generator, and all zeroes
+        // is a valid generator, as it only consists of numbers, pointers and
+        // optional function pointers.
+        //
+        // Allocation failures are handled by the box, which never returns a
+        // null pointer.
+        let generator = unsafe { Box::<cubiomes_sys::Generator>::new_zeroed().assume_init() };
+
+        Self { generator }
     }
 
     /// Sets the seed for the generator
@@ -187,15 +258,15 @@ impl Generator {
     /// Sets a new seed to the generator. This can either be used for
     /// initialization if the generator was generated with
     /// [`Self::new_without_seed()`] or changing the seed of the generator
-    pub fn apply_seed(&mut self, dimension: enums::Dimension, seed: i64) {
+    pub fn apply_seed(&mut self, dimension: enums::Dimension, seed: impl Into<WorldSeed>) {
         // SAFETY:
         // As the generator is correctly initialized and its fields are private
         // the applySeed function is only given valid instances of generator
         unsafe {
             cubiomes_sys::applySeed(
-                self.generator,
+                self.as_mut_ptr(),
                 dimension as i32,
-                transmute::<i64, u64>(seed),
+                transmute::<i64, u64>(seed.into().get()),
             );
         }
     }
@@ -209,7 +280,7 @@ impl Generator {
         // The scale enum guarantees that getBiomeAt is only given a scale of 1 or 4
         // As specified in its documentation
         unsafe {
-            match cubiomes_sys::getBiomeAt(self.generator, Scale::Block as i32, x, y, z) {
+            match cubiomes_sys::getBiomeAt(self.as_ptr(), Scale::Block as i32, x, y, z) {
                 -1 => Err(GeneratorError::GetBiomeAtFailure),
                 n => FromPrimitive::from_i32(n).ok_or(GeneratorError::BiomeIDOutOfRange(n)),
             }
@@ -219,32 +290,31 @@ impl Generator {
     /// Gets the seed of [self]
     #[must_use]
     pub fn seed(&self) -> i64 {
-        // SAFETY:
-        // The generator pointer can't be null as its been initialized
-        // when constructing this struct
-        unsafe { transmute::<u64, i64>((*self.generator).seed) }
+        self.generator.seed as i64
     }
 
     /// Gets the current dimension of the generator
     ///
-    /// # Panics
-    /// Panics if generator has an invalid dimension
-    pub fn dimension(&self) -> enums::Dimension {
-        // SAFETY: self has been initialized so ptr shouldn't be null
-        unsafe { Dimension::from_i32((*self.as_ptr()).dim).expect("dimension not valid") }
+    /// # Errors
+    /// Returns [`GeneratorError::InvalidDimension`] if the underlying generator
+    /// has been given a dimension which is not a valid [`Dimension`]. This can
+    /// only happen by modifying the generator through [`Self::as_mut_ptr()`].
+    pub fn dimension(&self) -> Result<enums::Dimension, GeneratorError> {
+        let dim = self.generator.dim;
+
+        Dimension::from_i32(dim).ok_or(GeneratorError::InvalidDimension(dim))
     }
 
     /// Gets the minecraft version of [self]
     ///
-    /// # Panics
-    /// Panics if the generator has an invalid MCVersion
-    #[must_use]
-    pub fn minecraft_version(&self) -> MCVersion {
-        // SAFETY:
-        // The generator pointer can't be null as its been initialized
-        // when constructing this struct
-        MCVersion::from_i32(unsafe { *self.generator }.mc)
-            .expect("Cubiomes generator has an invalid mc version")
+    /// # Errors
+    /// Returns [`GeneratorError::InvalidMinecraftVersion`] if the underlying
+    /// generator has a version which is not a valid [`MCVersion`]. This can
+    /// only happen by modifying the generator through [`Self::as_mut_ptr()`].
+    pub fn minecraft_version(&self) -> Result<MCVersion, GeneratorError> {
+        let mc = self.generator.mc;
+
+        MCVersion::from_i32(mc).ok_or(GeneratorError::InvalidMinecraftVersion(mc))
     }
 
     /// Generates a vector containing approximate surface height within the
@@ -253,16 +323,24 @@ impl Generator {
     /// The surface noise is generated at [Scale::Quad], eg. locations map 1:4.
     ///
     /// The vector contains the approximate height of each position and is
-    /// indexed as follows: `[buf_z * size_z + x]`. With buf_z being relative to
-    /// the top left position of the buffer.
+    /// indexed as follows: `[buf_z * size_x + buf_x]`. With buf_x and buf_z
+    /// being relative to the top left position of the buffer. See
+    /// [`Self::heightmap()`] for a typed version.
+    ///
+    /// Beta 1.7 and older need a [`BiomeNoise::Beta`], all later versions
+    /// need a [`BiomeNoise::Release`]. [`BiomeNoise::new()`] picks the right
+    /// one for a version. For beta the heights are only approximated for the
+    /// overworld.
     ///
     /// If noise is initialized for wrong version or dimension results may not
     /// makes sense.
     ///
-    /// # Panics
-    /// If attempting to generate surface noise for beta minecraft.
-    ///
-    /// Also panics if given an incorrect noicevariant.
+    /// # Errors
+    /// Returns [`GeneratorError::WrongSurfaceNoise`] if the noise variant
+    /// doesn't match the version of the generator. For beta versions returns
+    /// [`GeneratorError::WrongDimension`] if the generator isn't set to the
+    /// overworld, otherwise returns [`GeneratorError::SurfaceHeightFailure`] if
+    /// cubiomes can't generate the heights.
     pub fn approx_surface_noise(
         &self,
         x: i32,
@@ -270,49 +348,77 @@ impl Generator {
         size_x: u32,
         size_z: u32,
         surface_noise: &BiomeNoise,
-    ) -> Option<Vec<f32>> {
-        if self.minecraft_version() == MCVersion::MC_B1_7
-            || self.minecraft_version() == MCVersion::MC_B1_8
-        {
-            panic!("Surface height approximation not currently supported for beta minecraft")
-        } else {
-            let capacity = (size_x * size_z) as usize;
-
-            let mut buff = Vec::with_capacity(capacity);
-
-            let BiomeNoise::Release(surface_noise) = surface_noise else {
-                panic!("Tried to use beta noise with non beta generator")
-            };
-
-            // SAFETY: Foreign function is called with correct arguments
-            //
-            // buff can hold enough items to be filled
-            // ids can be null according to docs
-            // surface noise is initialized
-            let res = unsafe {
-                mapApproxHeight(
-                    buff.as_mut_ptr(),
-                    std::ptr::null_mut(),
-                    self.as_ptr(),
-                    surface_noise.as_ptr(),
-                    x,
-                    z,
-                    size_x as i32,
-                    size_z as i32,
-                )
-            };
-
-            if res != 0 {
-                return None;
+    ) -> Result<Vec<f32>, GeneratorError> {
+        let version = self.minecraft_version()?;
+        let is_beta = version <= MCVersion::MC_B1_7;
+
+        match surface_noise {
+            BiomeNoise::Beta(surface_noise) if is_beta => {
+                self.approx_surface_noise_beta(x, z, size_x, size_z, surface_noise)
             }
+            BiomeNoise::Release(surface_noise) if !is_beta => {
+                let capacity = (size_x * size_z) as usize;
+
+                let mut buff = Vec::with_capacity(capacity);
+
+                // SAFETY: Foreign function is called with correct arguments
+                //
+                // buff can hold enough items to be filled
+                // ids can be null according to docs
+                // surface noise is initialized
+                let res = unsafe {
+                    mapApproxHeight(
+                        buff.as_mut_ptr(),
+                        std::ptr::null_mut(),
+                        self.as_ptr(),
+                        surface_noise.as_ptr(),
+                        x,
+                        z,
+                        size_x as i32,
+                        size_z as i32,
+                    )
+                };
+
+                if res != 0 {
+                    return Err(GeneratorError::SurfaceHeightFailure(res));
+                }
+
+                // SAFETY: as buff was filled with ffi it should hold capacity elements
+                unsafe {
+                    buff.set_len(capacity);
+                }
 
-            // SAFETY: as buff was filled with ffi it should hold capacity elements
-            unsafe {
-                buff.set_len(capacity);
+                Ok(buff)
             }
+            _ => Err(GeneratorError::WrongSurfaceNoise(version)),
+        }
+    }
 
-            Some(buff)
+    /// Approximates beta surface heights, sampling the middle of each 1:4
+    /// position like cubiomes does for release versions
+    fn approx_surface_noise_beta(
+        &self,
+        x: i32,
+        z: i32,
+        size_x: u32,
+        size_z: u32,
+        surface_noise: &SurfaceNoiseBeta,
+    ) -> Result<Vec<f32>, GeneratorError> {
+        let mut buff = Vec::with_capacity((size_x * size_z) as usize);
+
+        for buf_z in 0..size_z as i32 {
+            for buf_x in 0..size_x as i32 {
+                let height = surface_noise.approx_surface(
+                    self,
+                    Scale::Quad.unscale_coord(x + buf_x) + 2,
+                    Scale::Quad.unscale_coord(z + buf_z) + 2,
+                )?;
+
+                buff.push(height as f32);
+            }
         }
+
+        Ok(buff)
     }
 
     /// Gets a raw mutable pointer to the underlying generator
@@ -331,7 +437,7 @@ impl Generator {
     /// Also keep in mind thread safety, if working in a multithreaded
     /// environment.
     pub unsafe fn as_mut_ptr(&mut self) -> *mut cubiomes_sys::Generator {
-        self.generator
+        &mut *self.generator
     }
 
     /// Gets a raw const pointer to the underlying generator
@@ -350,7 +456,58 @@ impl Generator {
     /// have exclusive access.
     #[must_use]
     pub unsafe fn as_ptr(&self) -> *const cubiomes_sys::Generator {
-        self.generator
+        &*self.generator
+    }
+
+    /// Checks that biomes can be generated for the range with this generator
+    ///
+    /// Cubiomes generates each dimension with its own scaled generation
+    /// function, which don't all support the same inputs:
+    ///
+    /// | Dimension | Versions        | Scales                         |
+    /// |-----------|-----------------|--------------------------------|
+    /// | Overworld | Before 1.18     | Up to [`Scale::HalfRegion`]    |
+    /// | Overworld | 1.18 and later  | All                            |
+    /// | Nether    | All             | All                            |
+    /// | End       | 1.0 and later   | All                            |
+    ///
+    /// The nether only has biomes since 1.16 and the end since 1.9, before that
+    /// they are filled with a single biome.
+    ///
+    /// The size of the range is also checked, see [`TryFromRangeError`].
+    ///
+    /// This is checked automatically by [`Cache::new()`], but can be used to
+    /// validate a request up front.
+    ///
+    /// [`TryFromRangeError`]: error::TryFromRangeError
+    pub fn validate_range(&self, range: &Range) -> Result<(), GeneratorError> {
+        cubiomes_sys::Range::try_from(*range)?;
+
+        let dimension = self.dimension()?;
+        let version = self.minecraft_version()?;
+
+        let supported = match dimension {
+            Dimension::DIM_OVERWORLD | Dimension::DIM_NETHER => true,
+            Dimension::DIM_END => version >= MCVersion::MC_1_0_0,
+            _ => false,
+        };
+
+        if !supported {
+            return Err(GeneratorError::UnsupportedDimension { dimension, version });
+        }
+
+        if dimension == Dimension::DIM_OVERWORLD
+            && version < MCVersion::MC_1_18_2
+            && range.scale > Scale::HalfRegion
+        {
+            return Err(GeneratorError::UnsupportedScale {
+                dimension,
+                version,
+                scale: range.scale,
+            });
+        }
+
+        Ok(())
     }
 
     fn min_cache_size_from_range(&self, range: Range) -> usize {
@@ -384,7 +541,7 @@ impl Generator {
         //
         // The requirement for this is checked from the source code and not from
         // documentation
-        unsafe { getMinCacheSize(self.generator, scale, size_x, size_y, size_z) }
+        unsafe { getMinCacheSize(self.as_ptr(), scale, size_x, size_y, size_z) }
     }
 
     /// Fills the provided cache from the generator
@@ -398,7 +555,7 @@ impl Generator {
         cache: &mut Cache,
     ) -> Result<(), GeneratorError> {
         let result_num = cubiomes_sys::genBiomes(
-            self.generator,
+            self.as_ptr(),
             cache.buffer.as_mut_ptr(),
             cache.range.try_into()?,
         );
@@ -433,8 +590,10 @@ impl Generator {
     /// Black corresponds to `height <= bottom` and White `height >= top`. The
     /// colors are mapped linearly in between the supplied values.
     ///
-    /// Returns none if generator cannot generate a heightmap. For example if
-    /// trying to generate nether heights or end before end existed.
+    /// # Errors
+    /// Returns an error if generator cannot generate a heightmap. For example
+    /// if trying to generate nether heights or end before end existed. See
+    /// [`Self::approx_surface_noise()`] for details.
     ///
     /// # Examples
     /// ```
@@ -451,23 +610,44 @@ impl Generator {
         bottom: f32,
         top: f32,
         surface_noise: &BiomeNoise,
-    ) -> Option<image::GrayImage> {
-        use image::GrayImage;
+    ) -> Result<image::GrayImage, GeneratorError> {
+        Ok(self
+            .heightmap(x, z, size_x, size_z, surface_noise)?
+            .to_gray_image(bottom, top))
+    }
+}
 
-        let buf = self.approx_surface_noise(x, z, size_x, size_z, surface_noise)?;
+/// The allocation of a [`Cache`], without a generator
+///
+/// The buffer can be used to create a new cache with [`Cache::from_buffer()`]
+/// without reallocating, even after the generator has been reseeded.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct CacheBuffer(Vec<i32>);
+
+impl CacheBuffer {
+    /// Creates a new empty buffer
+    ///
+    /// The buffer allocates when it is first used for a cache.
+    #[must_use]
+    pub const fn new() -> Self {
+        Self(Vec::new())
+    }
 
-        Some(GrayImage::from_fn(size_x, size_z, |img_x, img_z| {
-            [float_between(
-                buf[(img_z * size_z + img_x) as usize],
-                bottom,
-                top,
-            )]
-            .into()
-        }))
+    /// Creates a new buffer which can hold `capacity` biomes without
+    /// reallocating
+    #[must_use]
+    pub fn with_capacity(capacity: usize) -> Self {
+        Self(Vec::with_capacity(capacity))
+    }
+
+    /// Gets the amount of biomes the buffer can hold without reallocating
+    #[must_use]
+    pub fn capacity(&self) -> usize {
+        self.0.capacity()
     }
 }
 
-fn float_between(n: f32, bottom: f32, top: f32) -> u8 {
+pub(crate) fn float_between(n: f32, bottom: f32, top: f32) -> u8 {
     let range = top - bottom;
 
     ((n - bottom) * ((u8::MAX as f32) / range)).clamp(u8::MIN as f32, u8::MAX as f32) as u8
@@ -480,12 +660,43 @@ fn float_between(n: f32, bottom: f32, top: f32) -> u8 {
 /// cache is created or moved.
 ///
 /// The cache should be thought of as a view into the generator which it was
-/// created with.
-#[derive(Clone)]
+/// created with. To change the seed of the generator, the cache can be turned
+/// into a [`CacheBuffer`] with [`Cache::into_buffer()`] and the allocation
+/// reused with [`Cache::from_buffer()`].
 pub struct Cache<'generator> {
     buffer: Vec<i32>,
     range: Range,
     generator: &'generator Generator,
+    downsample_from: Option<Scale>,
+    // Holds the finer biomes of downsampled caches between fills
+    fine_buffer: Vec<i32>,
+}
+
+impl Clone for Cache<'_> {
+    /// Copies the biomes into a buffer which cubiomes can fill
+    ///
+    /// Cubiomes needs more space than the biomes which can be read from the
+    /// cache, so the clone reserves the same space as [`Self::new()`] and can
+    /// be moved with [`Self::move_cache()`] like the original.
+    fn clone(&self) -> Self {
+        let capacity = match self.downsample_from {
+            // Downsampled biomes are pushed one by one, cubiomes fills the
+            // fine buffer
+            Some(_) => self.buffer.len(),
+            None => self.generator.min_cache_size_from_range(self.range),
+        };
+
+        let mut buffer = Vec::with_capacity(capacity.max(self.buffer.len()));
+        buffer.extend_from_slice(&self.buffer);
+
+        Self {
+            buffer,
+            range: self.range,
+            generator: self.generator,
+            downsample_from: self.downsample_from,
+            fine_buffer: Vec::with_capacity(self.fine_buffer.capacity()),
+        }
+    }
 }
 
 //Custom dbg implementation, so we get the cache formatted as a table
@@ -507,7 +718,12 @@ impl Cache<'_> {
     ///
     /// This function creates a new [`Cache`] against this version of the
     /// generator, and fills it.
+    ///
+    /// Returns an error if the generator can't generate the range, see
+    /// [`Generator::validate_range()`] for what is supported.
     pub fn new(generator: &Generator, range: Range) -> Result<Cache<'_>, GeneratorError> {
+        generator.validate_range(&range)?;
+
         let cache_size = generator.min_cache_size_from_range(range);
         let cache = Vec::with_capacity(cache_size);
 
@@ -515,6 +731,154 @@ impl Cache<'_> {
             buffer: cache,
             range,
             generator,
+            downsample_from: None,
+            fine_buffer: Vec::new(),
+        };
+
+        cache.fill_cache().and(Ok(cache))
+    }
+
+    /// Generates a new cache reusing the allocation of a [`CacheBuffer`]
+    ///
+    /// This works like [`Self::new()`], but only allocates if the buffer is
+    /// too small for the range. This is useful for generating the same sized
+    /// area for many seeds.
+    ///
+    /// Returns an error if the generator can't generate the range, see
+    /// [`Generator::validate_range()`] for what is supported.
+    ///
+    /// # Examples
+    /// ```
+    /// use cubiomes::enums::{Dimension, MCVersion};
+    /// use cubiomes::generator::{Cache, CacheBuffer, Generator, GeneratorFlags, Range, Scale};
+    ///
+    /// let mut generator = Generator::new(
+    ///     MCVersion::MC_1_21_WD,
+    ///     0,
+    ///     Dimension::DIM_OVERWORLD,
+    ///     GeneratorFlags::empty(),
+    /// );
+    ///
+    /// let range = Range {
+    ///     scale: Scale::Chunk,
+    ///     x: -8,
+    ///     z: -8,
+    ///     size_x: 16,
+    ///     size_z: 16,
+    ///     y: 16,
+    ///     size_y: 0,
+    /// };
+    ///
+    /// let mut buffer = CacheBuffer::new();
+    ///
+    /// for seed in 0..4 {
+    ///     generator.apply_seed(Dimension::DIM_OVERWORLD, seed);
+    ///
+    ///     let cache = Cache::from_buffer(&generator, range, buffer).expect("range is valid");
+    ///     println!("{seed}: {:?}", cache.biome_at(8, 0, 8));
+    ///
+    ///     buffer = cache.into_buffer();
+    /// }
+    /// ```
+    pub fn from_buffer(
+        generator: &Generator,
+        range: Range,
+        buffer: CacheBuffer,
+    ) -> Result<Cache<'_>, GeneratorError> {
+        generator.validate_range(&range)?;
+
+        let mut buffer = buffer.0;
+        buffer.clear();
+        // The length is zero, so this reserves the whole minimum size
+        buffer.reserve(generator.min_cache_size_from_range(range));
+
+        let mut cache = Cache {
+            buffer,
+            range,
+            generator,
+            downsample_from: None,
+            fine_buffer: Vec::new(),
+        };
+
+        cache.fill_cache().and(Ok(cache))
+    }
+
+    /// Releases the generator, keeping the allocation of the cache
+    ///
+    /// See [`Self::from_buffer()`] for usage.
+    #[must_use]
+    pub fn into_buffer(self) -> CacheBuffer {
+        CacheBuffer(self.buffer)
+    }
+
+    /// Generates a new cache by downsampling biomes from a finer scale
+    ///
+    /// The biomes are generated at the `from` scale, and each position in the
+    /// cache gets the most common biome of the area it covers. When biomes are
+    /// tied, the one first found in the area going row by row is used.
+    ///
+    /// This is useful for scales the generator can't generate directly, for
+    /// example overview maps at [`Scale::DoubleRegion`] before 1.18. It is
+    /// slower than generating the scale directly, as every biome of the finer
+    /// scale has to be generated.
+    ///
+    /// Moving the cache with [`Self::move_cache()`] keeps downsampling.
+    ///
+    /// # Errors
+    /// Returns [`GeneratorError::InvalidDownsample`] if `from` is not finer
+    /// than the scale of the range, or the range is not a plane. Otherwise
+    /// returns an error if the generator can't generate the range at the
+    /// `from` scale, see [`Generator::validate_range()`].
+    ///
+    /// # Examples
+    /// ```
+    /// use cubiomes::enums::{Dimension, MCVersion};
+    /// use cubiomes::generator::{Cache, Generator, GeneratorFlags, Range, Scale};
+    ///
+    /// let generator = Generator::new(
+    ///     MCVersion::MC_1_16_5,
+    ///     -4172144997902289642,
+    ///     Dimension::DIM_OVERWORLD,
+    ///     GeneratorFlags::empty(),
+    /// );
+    ///
+    /// // A 32 000 block wide overview, which 1.16 can't generate directly
+    /// let range = Range {
+    ///     scale: Scale::OctupleRegion,
+    ///     x: -4,
+    ///     z: -4,
+    ///     size_x: 8,
+    ///     size_z: 8,
+    ///     y: 16,
+    ///     size_y: 0,
+    /// };
+    ///
+    /// let cache = Cache::new_downsampled(&generator, range, Scale::HalfRegion)
+    ///     .expect("1.16 can generate 1:256");
+    ///
+    /// assert_eq!(cache.as_vec().len(), 64);
+    /// ```
+    pub fn new_downsampled(
+        generator: &Generator,
+        range: Range,
+        from: Scale,
+    ) -> Result<Cache<'_>, GeneratorError> {
+        if from >= range.scale || range.size_y > 1 {
+            return Err(GeneratorError::InvalidDownsample {
+                from,
+                to: range.scale,
+            });
+        }
+
+        cubiomes_sys::Range::try_from(range)?;
+        generator.validate_range(&range.with_scale(from))?;
+
+        let mut cache = Cache {
+            buffer: Vec::with_capacity((range.size_x * range.size_z) as usize),
+            range,
+            generator,
+            downsample_from: Some(from),
+            fine_buffer: Vec::new(),
         };
 
         cache.fill_cache().and(Ok(cache))
@@ -522,6 +886,10 @@ impl Cache<'_> {
 
     /// Fills the cache so it can be read
     fn fill_cache(&mut self) -> Result<(), GeneratorError> {
+        if let Some(from) = self.downsample_from {
+            return self.fill_downsampled(from);
+        }
+
         // Safety:
         // As the cache holds a reference to the generator, the generator
         // could not have been modified after the vec was allocated so the
@@ -529,6 +897,75 @@ impl Cache<'_> {
         unsafe { self.generator.unchecked_generate_biomes_to_cache(self) }
     }
 
+    /// Fills the cache with the most common biomes of a finer cache
+    ///
+    /// The finer biomes are generated into a buffer kept by the cache, so
+    /// moving the cache doesn't reallocate.
+    fn fill_downsampled(&mut self, from: Scale) -> Result<(), GeneratorError> {
+        let fine_range = self.range.with_scale(from);
+
+        let mut fine = Cache {
+            buffer: std::mem::take(&mut self.fine_buffer),
+            range: fine_range,
+            generator: self.generator,
+            downsample_from: None,
+            fine_buffer: Vec::new(),
+        };
+
+        fine.buffer.clear();
+        // Only allocates on the first fill, as the size of the range is fixed
+        fine.buffer
+            .reserve(self.generator.min_cache_size_from_range(fine_range));
+
+        let filled = fine.fill_cache();
+        self.fine_buffer = fine.buffer;
+        filled?;
+
+        let fine = &self.fine_buffer;
+        let ratio = (self.range.scale as i32 / from as i32) as usize;
+        let fine_size_x = fine_range.size_x as usize;
+
+        self.buffer.clear();
+
+        // Biomes and how many times they appear in the area being downsampled
+        let mut counts: Vec<(i32, u32)> = Vec::with_capacity(ratio * ratio);
+
+        for z in 0..self.range.size_z as usize {
+            for x in 0..self.range.size_x as usize {
+                counts.clear();
+
+                for fine_z in z * ratio..(z + 1) * ratio {
+                    let row = &fine[fine_z * fine_size_x..(fine_z + 1) * fine_size_x];
+
+                    for biome in &row[x * ratio..(x + 1) * ratio] {
+                        match counts.iter_mut().find(|(counted, _)| counted == biome) {
+                            Some((_, count)) => *count += 1,
+                            None => counts.push((*biome, 1)),
+                        }
+                    }
+                }
+
+                let majority = counts
+                    .iter()
+                    .fold(
+                        (0, 0),
+                        |best, current| {
+                            if current.1 > best.1 {
+                                *current
+                            } else {
+                                best
+                            }
+                        },
+                    )
+                    .0;
+
+                self.buffer.push(majority);
+            }
+        }
+
+        Ok(())
+    }
+
     /// Gets a reference to the internal representation of the cache.
     ///
     /// The cache is a linear array which can be accessed at the following
@@ -581,6 +1018,13 @@ impl Cache<'_> {
         &self.range
     }
 
+    /// Gets a reference to the generator this cache was created with
+    #[inline]
+    #[must_use]
+    pub fn generator(&self) -> &Generator {
+        self.generator
+    }
+
     /// This function gets a biome at the specified point in the cache
     ///
     /// The specified point is relative to the left upper corner of
@@ -598,6 +1042,41 @@ impl Cache<'_> {
         enums::BiomeID::from_i32(raw_biomeid).ok_or(GeneratorError::BiomeIDOutOfRange(raw_biomeid))
     }
 
+    /// Counts how many positions of the cache have each biome
+    ///
+    /// The biomes are sorted from the most to the least common, biomes which
+    /// are equally common are sorted by their id. Biomes not in the cache are
+    /// left out.
+    ///
+    /// # Errors
+    /// Returns [`GeneratorError::BiomeIDOutOfRange`] if the cache contains a
+    /// value which is not a valid biome.
+    pub fn biome_counts(&self) -> Result<Vec<(enums::BiomeID, usize)>, GeneratorError> {
+        let mut raw_counts: BTreeMap<i32, usize> = BTreeMap::new();
+
+        for raw_biomeid in self
+            .buffer
+            .iter()
+            .take(self.calculate_readable_cache_length())
+        {
+            *raw_counts.entry(*raw_biomeid).or_default() += 1;
+        }
+
+        let mut counts = raw_counts
+            .into_iter()
+            .map(|(raw_biomeid, count)| {
+                enums::BiomeID::from_i32(raw_biomeid)
+                    .map(|biome| (biome, count))
+                    .ok_or(GeneratorError::BiomeIDOutOfRange(raw_biomeid))
+            })
+            .collect::<Result<Vec<_>, _>>()?;
+
+        // The sort is stable, so equally common biomes stay sorted by id
+        counts.sort_by_key(|(_, count)| Reverse(*count));
+
+        Ok(counts)
+    }
+
     /// Moves the cache to the new position without a reallocation and fills it.
     ///
     /// This function can be used to generate multiple areas in sequence,
@@ -630,23 +1109,30 @@ impl Cache<'_> {
     /// to depend on the image crate in your project, as we dont re-export the
     /// image crate.
     ///
-    /// # Panics
-    /// Panics if the cache has not been filled.
+    /// # Errors
+    /// Returns an error if the cache contains a value which is not a valid
+    /// biome, see [`Self::biome_at()`].
     ///
     /// # Examples
     /// ```
     #[doc = include_str!("../../examples/generate_image.rs")]
     /// ```
     #[cfg(feature = "image")]
-    pub fn to_image(&self, color_map: crate::colors::BiomeColorMap) -> image::RgbImage {
+    pub fn to_image(
+        &self,
+        color_map: crate::colors::BiomeColorMap,
+    ) -> Result<image::RgbImage, GeneratorError> {
         use image::RgbImage;
 
-        RgbImage::from_fn(self.range.size_x, self.range.size_z, |x, z| {
-            color_map[self
-                .biome_at(x, 0, z)
-                .expect("Failed to get biome within cache (cache is probably unitialized)")]
-            .into()
-        })
+        let mut img = RgbImage::new(self.range.size_x, self.range.size_z);
+
+        for z in 0..self.range.size_z {
+            for x in 0..self.range.size_x {
+                img.put_pixel(x, z, color_map[self.biome_at(x, 0, z)?].into());
+            }
+        }
+
+        Ok(img)
     }
 
     /// Calculates the actual size of readable data within the cache
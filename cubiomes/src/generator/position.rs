@@ -40,6 +40,16 @@ impl BlockPosition {
     pub fn scale_by_num(&self, scale: i32) -> (i32, i32) {
         (self.x.div_euclid(scale), self.z.div_euclid(scale))
     }
+
+    /// Calculates the horizontal euclidean distance to another position in
+    /// blocks
+    #[must_use]
+    pub fn distance_to(&self, other: BlockPosition) -> f64 {
+        let dx = (other.x as i64 - self.x as i64) as f64;
+        let dz = (other.z as i64 - self.z as i64) as f64;
+
+        dx.hypot(dz)
+    }
 }
 
 impl From<Pos> for BlockPosition {
@@ -1,4 +1,7 @@
-use super::error::{GeneratorError, TryFromRangeError};
+use super::{
+    error::{GeneratorError, TryFromRangeError},
+    position::BlockPosition,
+};
 
 /// A scale for the [Range]
 ///
@@ -17,6 +20,16 @@ pub enum Scale {
     QuadChunk = 64,
     /// A scale of 1:256, half of a minecraft region
     HalfRegion = 256,
+    /// A scale of 1:1024, the size of 2 by 2 minecraft regions
+    ///
+    /// Only supported by some generators, see
+    /// [`super::Generator::validate_range()`]
+    DoubleRegion = 1024,
+    /// A scale of 1:4096, the size of 8 by 8 minecraft regions
+    ///
+    /// Only supported by some generators, see
+    /// [`super::Generator::validate_range()`]
+    OctupleRegion = 4096,
 }
 
 impl Scale {
@@ -111,6 +124,94 @@ impl Range {
                 && (self.scale.scale_coord(z) < (self.z + self.size_z as i32)))
     }
 
+    /// Gets the block position of the top left corner of this range
+    #[must_use]
+    pub fn min_block_position(&self) -> BlockPosition {
+        BlockPosition::from_scaled(self.x, self.z, self.scale)
+    }
+
+    /// Gets the block position just outside the bottom right corner of this
+    /// range
+    ///
+    /// Together with [`Self::min_block_position()`] this describes the
+    /// half-open area of blocks covered by the range.
+    #[must_use]
+    pub fn max_block_position(&self) -> BlockPosition {
+        BlockPosition::from_scaled(
+            self.x + self.size_x as i32,
+            self.z + self.size_z as i32,
+            self.scale,
+        )
+    }
+
+    /// Converts the range to another scale, covering the same area
+    ///
+    /// If the area doesn't line up with the new scale, the range is grown so it
+    /// covers the whole area. Converting to a finer scale is always exact.
+    ///
+    /// The vertical position is converted between the 1:1 y coordinate of
+    /// [`Scale::Block`] and the 1:4 y coordinate of the other scales. A size_y
+    /// of 0 or 1 is kept as is, as it describes a plane.
+    ///
+    /// # Examples
+    /// ```
+    /// use cubiomes::generator::{Range, Scale};
+    ///
+    /// let range = Range {
+    ///     scale: Scale::DoubleRegion,
+    ///     x: -1,
+    ///     z: 2,
+    ///     size_x: 3,
+    ///     size_z: 4,
+    ///     y: 16,
+    ///     size_y: 0,
+    /// };
+    ///
+    /// let fine = range.with_scale(Scale::HalfRegion);
+    ///
+    /// assert_eq!((fine.x, fine.z, fine.size_x, fine.size_z), (-4, 8, 12, 16));
+    /// ```
+    #[must_use]
+    pub fn with_scale(&self, scale: Scale) -> Range {
+        let min = self.min_block_position();
+        let max = self.max_block_position();
+        let new_scale = scale as i32;
+
+        let x = min.x.div_euclid(new_scale);
+        let z = min.z.div_euclid(new_scale);
+        let end_x = (max.x + new_scale - 1).div_euclid(new_scale);
+        let end_z = (max.z + new_scale - 1).div_euclid(new_scale);
+
+        let (y, size_y) = match (self.scale, scale) {
+            (Scale::Block, Scale::Block) => (self.y, self.size_y),
+            (Scale::Block, _) => (
+                self.y.div_euclid(4),
+                match self.size_y {
+                    0 | 1 => self.size_y,
+                    n => ((self.y + n as i32 + 3).div_euclid(4) - self.y.div_euclid(4)) as u32,
+                },
+            ),
+            (_, Scale::Block) => (
+                self.y * 4,
+                match self.size_y {
+                    0 | 1 => self.size_y,
+                    n => n * 4,
+                },
+            ),
+            _ => (self.y, self.size_y),
+        };
+
+        Range {
+            scale,
+            x,
+            z,
+            size_x: (end_x - x) as u32,
+            size_z: (end_z - z) as u32,
+            y,
+            size_y,
+        }
+    }
+
     /// Tries to calculate a coordinate relative to this range.
     ///
     /// Tries to turn a global minecraft coordinate, to one inside this cache.
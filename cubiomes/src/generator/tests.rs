@@ -1,9 +1,214 @@
-use super::{position::BlockPosition, Generator};
+use super::{
+    error::GeneratorError, position::BlockPosition, Cache, CacheBuffer, Generator, Heightmap,
+    Layer, Range, Scale,
+};
 use crate::enums::*;
-use crate::structures::StructureRegion;
+use crate::noise::{BiomeNoise, SurfaceNoiseBeta};
+use crate::structures::{StructureGenerationError, StructureRegion};
+use std::collections::HashMap;
+
+const SCALES: [Scale; 7] = [
+    Scale::Block,
+    Scale::Quad,
+    Scale::Chunk,
+    Scale::QuadChunk,
+    Scale::HalfRegion,
+    Scale::DoubleRegion,
+    Scale::OctupleRegion,
+];
+
+const VERSIONS: [MCVersion; 10] = [
+    MCVersion::MC_B1_7,
+    MCVersion::MC_B1_8,
+    MCVersion::MC_1_0_0,
+    MCVersion::MC_1_7_10,
+    MCVersion::MC_1_12_2,
+    MCVersion::MC_1_15_2,
+    MCVersion::MC_1_16_5,
+    MCVersion::MC_1_17_1,
+    MCVersion::MC_1_18_2,
+    MCVersion::MC_1_21_WD,
+];
+
+/// Gets whether the nether or end can be generated in the version, following
+/// the table of [`Generator::validate_range()`]
+fn expected_support(dimension: Dimension, version: MCVersion) -> Result<(), GeneratorError> {
+    if dimension == Dimension::DIM_END && version < MCVersion::MC_1_0_0 {
+        return Err(GeneratorError::UnsupportedDimension { dimension, version });
+    }
+
+    Ok(())
+}
+
+/// Generates every scale for the dimension in every version, checking that
+/// the cache is either generated with biomes of the dimension or fails with the
+/// error expected by [`expected_support()`]
+fn check_dimension_scales(dimension: Dimension, biomes: &[BiomeID]) {
+    for version in VERSIONS {
+        let generator = Generator::new(
+            version,
+            -4804349703814383506,
+            dimension,
+            super::GeneratorFlags::empty(),
+        );
+
+        for scale in SCALES {
+            let range = Range {
+                scale,
+                x: -8,
+                z: -8,
+                size_x: 16,
+                size_z: 16,
+                y: 16,
+                size_y: 0,
+            };
+
+            match (
+                expected_support(dimension, version),
+                Cache::new(&generator, range),
+            ) {
+                (Ok(()), Ok(cache)) => {
+                    for z in 0..16 {
+                        for x in 0..16 {
+                            let biome = cache.biome_at(x, 0, z).unwrap_or_else(|err| {
+                                panic!("{dimension} {version} 1:{}: {err}", scale as i32)
+                            });
+                            assert!(
+                                biomes.contains(&biome),
+                                "{biome:?} in {dimension} {version} 1:{}",
+                                scale as i32
+                            );
+                        }
+                    }
+                }
+                (Err(expected), Err(err)) => assert_eq!(expected, err),
+                (expected, cache) => panic!(
+                    "{dimension} {version} 1:{} expected {expected:?} but generated {:?}",
+                    scale as i32,
+                    cache.map(|_| ())
+                ),
+            }
+        }
+    }
+}
+
+#[test]
+#[cfg_attr(miri, ignore)]
+fn nether_scales() {
+    check_dimension_scales(
+        Dimension::DIM_NETHER,
+        &[
+            BiomeID::nether_wastes,
+            BiomeID::soul_sand_valley,
+            BiomeID::crimson_forest,
+            BiomeID::warped_forest,
+            BiomeID::basalt_deltas,
+        ],
+    );
+}
+
+#[test]
+#[cfg_attr(miri, ignore)]
+fn end_scales() {
+    check_dimension_scales(
+        Dimension::DIM_END,
+        &[
+            BiomeID::the_end,
+            BiomeID::small_end_islands,
+            BiomeID::end_midlands,
+            BiomeID::end_highlands,
+            BiomeID::end_barrens,
+        ],
+    );
+}
+
+#[test]
+#[cfg_attr(miri, ignore)]
+fn coarse_scales_in_every_dimension() {
+    let range = Range {
+        scale: Scale::DoubleRegion,
+        x: -2,
+        z: -2,
+        size_x: 4,
+        size_z: 4,
+        y: 16,
+        size_y: 0,
+    };
+
+    for dimension in [Dimension::DIM_NETHER, Dimension::DIM_END] {
+        let generator = Generator::new(
+            MCVersion::MC_1_21_WD,
+            -4804349703814383506,
+            dimension,
+            super::GeneratorFlags::empty(),
+        );
+
+        for scale in [Scale::DoubleRegion, Scale::OctupleRegion] {
+            assert!(
+                Cache::new(&generator, Range { scale, ..range }).is_ok(),
+                "{dimension} 1:{}",
+                scale as i32
+            );
+        }
+    }
+
+    for version in VERSIONS {
+        let generator = Generator::new(
+            version,
+            -4804349703814383506,
+            Dimension::DIM_OVERWORLD,
+            super::GeneratorFlags::empty(),
+        );
+
+        let expected = if version < MCVersion::MC_1_18_2 {
+            Err(GeneratorError::UnsupportedScale {
+                dimension: Dimension::DIM_OVERWORLD,
+                version,
+                scale: Scale::DoubleRegion,
+            })
+        } else {
+            Ok(())
+        };
+
+        assert_eq!(
+            Cache::new(&generator, range).map(|_| ()),
+            expected,
+            "{version}"
+        );
+    }
+}
 
 #[test]
-#[should_panic]
+#[cfg_attr(miri, ignore)]
+fn no_end_in_beta() {
+    let generator = Generator::new(
+        MCVersion::MC_B1_8,
+        -4804349703814383506,
+        Dimension::DIM_END,
+        super::GeneratorFlags::empty(),
+    );
+
+    let range = Range {
+        scale: Scale::Quad,
+        x: 0,
+        z: 0,
+        size_x: 16,
+        size_z: 16,
+        y: 16,
+        size_y: 0,
+    };
+
+    assert_eq!(
+        Cache::new(&generator, range).map(|_| ()),
+        Err(GeneratorError::UnsupportedDimension {
+            dimension: Dimension::DIM_END,
+            version: MCVersion::MC_B1_8,
+        })
+    );
+}
+
+#[test]
+#[cfg_attr(miri, ignore)]
 fn no_structure_found() {
     let seed = -834578276348761;
     let minecraft_version = MCVersion::MC_1_17_1;
@@ -23,11 +228,37 @@ fn no_structure_found() {
         super::GeneratorFlags::empty(),
     );
 
-    #[allow(clippy::unwrap_used)]
-    generator.try_generate_structure_in_region(pos).unwrap();
+    assert_eq!(generator.try_generate_structure_in_region(pos), Ok(None));
+}
+
+#[test]
+#[cfg_attr(miri, ignore)]
+fn structure_region_version_mismatch() {
+    let pos = StructureRegion::from_block_position(
+        BlockPosition::new(3888, 2656),
+        MCVersion::MC_1_17_1,
+        StructureType::Outpost,
+    )
+    .expect("Failed to generate region position");
+
+    let mut generator = Generator::new(
+        MCVersion::MC_1_21_WD,
+        -5134222539607995087,
+        Dimension::DIM_OVERWORLD,
+        super::GeneratorFlags::empty(),
+    );
+
+    assert_eq!(
+        generator.try_generate_structure_in_region(pos),
+        Err(StructureGenerationError::VersionMismatch {
+            generator: MCVersion::MC_1_21_WD,
+            region: MCVersion::MC_1_17_1,
+        })
+    );
 }
 
 #[test]
+#[cfg_attr(miri, ignore)]
 fn test_structure_generation() {
     let seed = -5134222539607995087;
     let minecraft_version = MCVersion::MC_1_21_WD;
@@ -51,10 +282,12 @@ fn test_structure_generation() {
 
     generator
         .try_generate_structure_in_region(pos)
+        .expect("Region matches the generator")
         .expect("Couldn't find structure when there should be a structure");
 }
 
 #[test]
+#[cfg_attr(miri, ignore)]
 fn test_structure_generation_negative() {
     let seed = -5134222539607995087;
     let minecraft_version = MCVersion::MC_1_21_WD;
@@ -78,5 +311,720 @@ fn test_structure_generation_negative() {
 
     generator
         .try_generate_structure_in_region(pos)
+        .expect("Region matches the generator")
         .expect("Couldn't find structure when there should be a structure");
 }
+
+#[test]
+#[cfg_attr(miri, ignore)]
+fn layer_scales() {
+    let generator = Generator::new(
+        MCVersion::MC_1_16_5,
+        -4804349703814383506,
+        Dimension::DIM_OVERWORLD,
+        super::GeneratorFlags::empty(),
+    );
+
+    for (layer, scale) in [
+        (Layer::Continent, 4096),
+        (Layer::Heat, 1024),
+        (Layer::OceanTemperature, 256),
+        (Layer::Hills, 64),
+        (Layer::Shore, 16),
+        (Layer::River, 4),
+        (Layer::Voronoi, 1),
+    ] {
+        let area = generator
+            .generate_layer(layer, -8, -8, 16, 16)
+            .expect("1.16 has all of these layers");
+
+        assert_eq!(area.scale, scale, "{layer:?}");
+        assert_eq!(area.values.len(), 16 * 16);
+    }
+}
+
+#[test]
+#[cfg_attr(miri, ignore)]
+fn final_layer_matches_cache() {
+    let generator = Generator::new(
+        MCVersion::MC_1_12_2,
+        -4804349703814383506,
+        Dimension::DIM_OVERWORLD,
+        super::GeneratorFlags::empty(),
+    );
+
+    let area = generator
+        .generate_layer(Layer::RiverMix, -32, -32, 64, 64)
+        .expect("1.12 has a river mix layer");
+
+    let cache = Cache::new(
+        &generator,
+        Range {
+            scale: Scale::Quad,
+            x: -32,
+            z: -32,
+            size_x: 64,
+            size_z: 64,
+            y: 16,
+            size_y: 0,
+        },
+    )
+    .expect("range is valid");
+
+    for z in 0..64 {
+        for x in 0..64 {
+            assert_eq!(
+                area.biome_at(x as i32 - 32, z as i32 - 32)
+                    .expect("river mix produces biomes"),
+                cache.biome_at(x, 0, z).expect("position is in the cache"),
+            );
+        }
+    }
+}
+
+#[test]
+#[cfg_attr(miri, ignore)]
+fn layers_outside_stack() {
+    let overworld = |version| {
+        Generator::new(
+            version,
+            -4804349703814383506,
+            Dimension::DIM_OVERWORLD,
+            super::GeneratorFlags::empty(),
+        )
+    };
+
+    assert_eq!(
+        overworld(MCVersion::MC_1_18_2).generate_layer(Layer::Continent, 0, 0, 4, 4),
+        Err(GeneratorError::UnsupportedVersion(MCVersion::MC_1_18_2))
+    );
+    assert_eq!(
+        overworld(MCVersion::MC_B1_7).generate_layer(Layer::Continent, 0, 0, 4, 4),
+        Err(GeneratorError::UnsupportedVersion(MCVersion::MC_B1_7))
+    );
+    assert_eq!(
+        overworld(MCVersion::MC_1_12_2).generate_layer(Layer::OceanTemperature, 0, 0, 4, 4),
+        Err(GeneratorError::UnsupportedVersion(MCVersion::MC_1_12_2))
+    );
+
+    let nether = Generator::new(
+        MCVersion::MC_1_16_5,
+        -4804349703814383506,
+        Dimension::DIM_NETHER,
+        super::GeneratorFlags::empty(),
+    );
+
+    assert_eq!(
+        nether.generate_layer(Layer::Continent, 0, 0, 4, 4),
+        Err(GeneratorError::WrongDimension {
+            expected: Dimension::DIM_OVERWORLD,
+            actual: Dimension::DIM_NETHER,
+        })
+    );
+}
+
+#[test]
+#[cfg_attr(miri, ignore)]
+fn large_scales_need_biome_noise() {
+    let range = Range {
+        scale: Scale::DoubleRegion,
+        x: -2,
+        z: -2,
+        size_x: 4,
+        size_z: 4,
+        y: 16,
+        size_y: 0,
+    };
+
+    for version in VERSIONS {
+        let generator = Generator::new(
+            version,
+            -4804349703814383506,
+            Dimension::DIM_OVERWORLD,
+            super::GeneratorFlags::empty(),
+        );
+
+        let cache = Cache::new(&generator, range);
+
+        if version >= MCVersion::MC_1_18_2 {
+            assert!(cache.is_ok(), "{version}");
+        } else {
+            assert_eq!(
+                cache.map(|_| ()),
+                Err(GeneratorError::UnsupportedScale {
+                    dimension: Dimension::DIM_OVERWORLD,
+                    version,
+                    scale: Scale::DoubleRegion,
+                })
+            );
+        }
+    }
+}
+
+#[test]
+#[cfg_attr(miri, ignore)]
+fn coarse_biomes_appear_in_fine_map() {
+    let generator = Generator::new(
+        MCVersion::MC_1_21_WD,
+        -4804349703814383506,
+        Dimension::DIM_OVERWORLD,
+        super::GeneratorFlags::empty(),
+    );
+
+    let range = Range {
+        scale: Scale::DoubleRegion,
+        x: -2,
+        z: -2,
+        size_x: 4,
+        size_z: 4,
+        y: 16,
+        size_y: 0,
+    };
+
+    let coarse = Cache::new(&generator, range).expect("1.21 generates 1:1024");
+    let fine = Cache::new(&generator, range.with_scale(Scale::Chunk)).expect("1.21 generates 1:16");
+
+    for biome in coarse.as_vec() {
+        assert!(fine.as_vec().contains(biome), "{biome} not in fine map");
+    }
+}
+
+#[test]
+#[cfg_attr(miri, ignore)]
+fn downsampled_is_majority_of_fine_map() {
+    let generator = Generator::new(
+        MCVersion::MC_1_16_5,
+        -4804349703814383506,
+        Dimension::DIM_OVERWORLD,
+        super::GeneratorFlags::empty(),
+    );
+
+    let range = Range {
+        scale: Scale::DoubleRegion,
+        x: -3,
+        z: -3,
+        size_x: 6,
+        size_z: 6,
+        y: 16,
+        size_y: 0,
+    };
+
+    let coarse =
+        Cache::new_downsampled(&generator, range, Scale::QuadChunk).expect("1.16 generates 1:64");
+    let fine =
+        Cache::new(&generator, range.with_scale(Scale::QuadChunk)).expect("1.16 generates 1:64");
+
+    assert_eq!(fine.range().size_x, 6 * 16);
+
+    for z in 0..6 {
+        for x in 0..6 {
+            let biome = coarse.biome_at(x, 0, z).expect("position is in the cache");
+
+            let mut counts: HashMap<BiomeID, usize> = HashMap::new();
+
+            for fine_z in z * 16..(z + 1) * 16 {
+                for fine_x in x * 16..(x + 1) * 16 {
+                    let fine_biome = fine
+                        .biome_at(fine_x, 0, fine_z)
+                        .expect("position is in the cache");
+
+                    *counts.entry(fine_biome).or_default() += 1;
+                }
+            }
+
+            let most_common = counts.values().copied().max().unwrap_or_default();
+
+            assert_eq!(
+                counts.get(&biome).copied(),
+                Some(most_common),
+                "{biome:?} is not the most common biome of {counts:?}"
+            );
+        }
+    }
+}
+
+#[test]
+#[cfg_attr(miri, ignore)]
+fn moved_downsampled_cache_matches_new_cache() {
+    let generator = Generator::new(
+        MCVersion::MC_1_16_5,
+        -4804349703814383506,
+        Dimension::DIM_OVERWORLD,
+        super::GeneratorFlags::empty(),
+    );
+
+    let range = Range {
+        scale: Scale::DoubleRegion,
+        x: -3,
+        z: -3,
+        size_x: 6,
+        size_z: 6,
+        y: 16,
+        size_y: 0,
+    };
+
+    let mut moved =
+        Cache::new_downsampled(&generator, range, Scale::QuadChunk).expect("1.16 generates 1:64");
+    moved.move_cache(5, 16, -2).expect("1.16 generates 1:64");
+
+    let new = Cache::new_downsampled(
+        &generator,
+        Range {
+            x: 5,
+            z: -2,
+            ..range
+        },
+        Scale::QuadChunk,
+    )
+    .expect("1.16 generates 1:64");
+
+    assert_eq!(moved.as_vec(), new.as_vec());
+}
+
+#[test]
+#[cfg_attr(miri, ignore)]
+fn downsample_needs_finer_scale() {
+    let generator = Generator::new(
+        MCVersion::MC_1_16_5,
+        -4804349703814383506,
+        Dimension::DIM_OVERWORLD,
+        super::GeneratorFlags::empty(),
+    );
+
+    let range = Range {
+        scale: Scale::Chunk,
+        x: 0,
+        z: 0,
+        size_x: 4,
+        size_z: 4,
+        y: 16,
+        size_y: 0,
+    };
+
+    assert_eq!(
+        Cache::new_downsampled(&generator, range, Scale::HalfRegion).map(|_| ()),
+        Err(GeneratorError::InvalidDownsample {
+            from: Scale::HalfRegion,
+            to: Scale::Chunk,
+        })
+    );
+}
+
+#[test]
+fn range_with_coarser_scale_covers_area() {
+    let range = Range {
+        scale: Scale::Block,
+        x: -10,
+        z: 5,
+        size_x: 20,
+        size_z: 11,
+        y: 70,
+        size_y: 0,
+    };
+
+    assert_eq!(
+        range.with_scale(Scale::Chunk),
+        Range {
+            scale: Scale::Chunk,
+            x: -1,
+            z: 0,
+            size_x: 2,
+            size_z: 1,
+            y: 17,
+            size_y: 0,
+        }
+    );
+}
+
+#[test]
+#[cfg_attr(miri, ignore)]
+fn surface_heights_in_every_version() {
+    for version in VERSIONS {
+        let seed = -4804349703814383506;
+        let generator = Generator::new(
+            version,
+            seed,
+            Dimension::DIM_OVERWORLD,
+            super::GeneratorFlags::empty(),
+        );
+        let noise = BiomeNoise::new(version, Dimension::DIM_OVERWORLD, seed);
+
+        let heights = generator
+            .approx_surface_noise(-16, -16, 32, 32, &noise)
+            .unwrap_or_else(|err| panic!("no heights for {version}: {err}"));
+
+        assert_eq!(heights.len(), 32 * 32);
+        assert!(
+            heights
+                .iter()
+                .all(|height| (-64.0..=320.0).contains(height)),
+            "{version}"
+        );
+        assert!(
+            heights.iter().any(|height| *height != heights[0]),
+            "flat terrain in {version}"
+        );
+    }
+}
+
+#[test]
+#[cfg_attr(miri, ignore)]
+fn beta_surface_matches_heightmap() {
+    let seed = -4804349703814383506;
+    let generator = Generator::new(
+        MCVersion::MC_B1_7,
+        seed,
+        Dimension::DIM_OVERWORLD,
+        super::GeneratorFlags::empty(),
+    );
+    let noise = SurfaceNoiseBeta::new(seed);
+
+    let heights = generator
+        .approx_surface_noise(0, 0, 8, 8, &BiomeNoise::Beta(SurfaceNoiseBeta::new(seed)))
+        .expect("beta overworld has heights");
+
+    for z in 0..8 {
+        for x in 0..8 {
+            let height = noise
+                .approx_surface(&generator, x * 4 + 2, z * 4 + 2)
+                .expect("generator is beta overworld");
+
+            assert_eq!(heights[(z * 8 + x) as usize], height as f32);
+        }
+    }
+}
+
+#[test]
+#[cfg_attr(miri, ignore)]
+fn surface_noise_must_match_version() {
+    let seed = -4804349703814383506;
+    let generator = Generator::new(
+        MCVersion::MC_B1_7,
+        seed,
+        Dimension::DIM_OVERWORLD,
+        super::GeneratorFlags::empty(),
+    );
+    let noise = BiomeNoise::new(MCVersion::MC_1_0_0, Dimension::DIM_OVERWORLD, seed);
+
+    assert_eq!(
+        generator.approx_surface_noise(0, 0, 8, 8, &noise),
+        Err(GeneratorError::WrongSurfaceNoise(MCVersion::MC_B1_7))
+    );
+}
+
+#[test]
+#[cfg_attr(miri, ignore)]
+fn beta_surface_needs_beta_generator() {
+    let seed = -4804349703814383506;
+    let generator = Generator::new(
+        MCVersion::MC_1_0_0,
+        seed,
+        Dimension::DIM_OVERWORLD,
+        super::GeneratorFlags::empty(),
+    );
+
+    assert_eq!(
+        SurfaceNoiseBeta::new(seed).approx_surface(&generator, 0, 0),
+        Err(GeneratorError::UnsupportedVersion(MCVersion::MC_1_0_0))
+    );
+}
+
+#[test]
+#[cfg_attr(miri, ignore)]
+fn cloned_generator_outlives_original() {
+    let range = Range {
+        scale: Scale::Quad,
+        x: -32,
+        z: -32,
+        size_x: 64,
+        size_z: 64,
+        y: 16,
+        size_y: 0,
+    };
+
+    for version in VERSIONS {
+        let generator = Generator::new(
+            version,
+            -4804349703814383506,
+            Dimension::DIM_OVERWORLD,
+            super::GeneratorFlags::LargeBiomes,
+        );
+        let expected = Cache::new(&generator, range)
+            .expect("range is valid")
+            .as_vec()
+            .clone();
+
+        let clone = generator.clone();
+        drop(generator);
+
+        assert_eq!(clone.seed(), -4804349703814383506);
+        assert_eq!(clone.dimension(), Ok(Dimension::DIM_OVERWORLD));
+        assert_eq!(
+            Cache::new(&clone, range).expect("range is valid").as_vec(),
+            &expected,
+            "{version}"
+        );
+    }
+}
+
+#[test]
+#[cfg_attr(miri, ignore)]
+fn clone_unseeded_generator() {
+    // SAFETY: The clone is seeded before generating anything
+    let generator = unsafe {
+        Generator::new_without_seed(MCVersion::MC_1_21_WD, super::GeneratorFlags::empty())
+    };
+
+    let mut clone = generator.clone();
+    assert_eq!(clone.dimension(), Ok(Dimension::DIM_UNDEF));
+
+    clone.apply_seed(Dimension::DIM_OVERWORLD, 5);
+    assert!(clone.get_biome_at(0, 64, 0).is_ok());
+}
+
+#[test]
+#[cfg_attr(miri, ignore)]
+fn cache_buffer_is_reused_after_reseed() {
+    let range = Range {
+        scale: Scale::Chunk,
+        x: -16,
+        z: -16,
+        size_x: 32,
+        size_z: 32,
+        y: 16,
+        size_y: 0,
+    };
+
+    let mut generator = Generator::new(
+        MCVersion::MC_1_21_WD,
+        1,
+        Dimension::DIM_OVERWORLD,
+        super::GeneratorFlags::empty(),
+    );
+
+    let cache = Cache::new(&generator, range).expect("range is valid");
+    let allocation = cache.as_vec().as_ptr();
+    let mut buffer = cache.into_buffer();
+
+    for seed in 2..6 {
+        generator.apply_seed(Dimension::DIM_OVERWORLD, seed);
+
+        let cache = Cache::from_buffer(&generator, range, buffer).expect("range is valid");
+        let fresh = Cache::new(&generator, range).expect("range is valid");
+
+        assert_eq!(cache.as_vec().as_ptr(), allocation);
+        assert_eq!(cache.as_vec(), fresh.as_vec());
+
+        buffer = cache.into_buffer();
+    }
+}
+
+#[test]
+#[cfg_attr(miri, ignore)]
+fn small_cache_buffer_grows() {
+    let generator = Generator::new(
+        MCVersion::MC_1_21_WD,
+        1,
+        Dimension::DIM_OVERWORLD,
+        super::GeneratorFlags::empty(),
+    );
+
+    let cache = Cache::from_buffer(
+        &generator,
+        Range {
+            scale: Scale::Block,
+            x: 0,
+            z: 0,
+            size_x: 48,
+            size_z: 48,
+            y: 64,
+            size_y: 0,
+        },
+        CacheBuffer::with_capacity(4),
+    )
+    .expect("range is valid");
+
+    assert_eq!(cache.as_vec().len(), 48 * 48);
+}
+
+#[test]
+#[cfg_attr(miri, ignore)]
+fn cloned_cache_can_be_moved() {
+    let generator = Generator::new(
+        MCVersion::MC_1_16_5,
+        1,
+        Dimension::DIM_OVERWORLD,
+        super::GeneratorFlags::empty(),
+    );
+
+    let range = Range {
+        scale: Scale::Block,
+        x: 0,
+        z: 0,
+        size_x: 48,
+        size_z: 48,
+        y: 64,
+        size_y: 0,
+    };
+
+    let cache = Cache::new(&generator, range).expect("range is valid");
+    let mut clone = cache.clone();
+
+    assert_eq!(clone.as_vec(), cache.as_vec());
+    assert!(clone.as_vec().capacity() >= generator.min_cache_size_from_range(range));
+
+    clone.move_cache(100, 64, -50).expect("range is valid");
+
+    let fresh = Cache::new(
+        &generator,
+        Range {
+            x: 100,
+            z: -50,
+            ..range
+        },
+    )
+    .expect("range is valid");
+
+    assert_eq!(clone.as_vec(), fresh.as_vec());
+}
+
+#[test]
+fn heightmap_indexing() {
+    let heights = (0..12).map(|height| height as f32).collect();
+    let heightmap = Heightmap::new(-2, 5, 4, 3, heights).expect("12 heights fit in 4x3");
+
+    assert_eq!(heightmap.get(3, 0), Some(3.0));
+    assert_eq!(heightmap.get(1, 2), Some(9.0));
+    assert_eq!(heightmap.get(4, 0), None);
+    assert_eq!(heightmap.get(0, 3), None);
+
+    // Block -8 is the start of quad -2, and block 20 the start of quad 5
+    assert_eq!(heightmap.height_at(-8, 20), Some(0.0));
+    assert_eq!(heightmap.height_at(-5, 27), Some(4.0));
+    assert_eq!(heightmap.height_at(7, 28), Some(11.0));
+    assert_eq!(heightmap.height_at(-9, 20), None);
+    assert_eq!(heightmap.height_at(8, 20), None);
+
+    assert_eq!(heightmap.min_max(), Some((0.0, 11.0)));
+    assert_eq!(Heightmap::new(0, 0, 4, 4, vec![0.0; 12]), None);
+}
+
+#[test]
+fn heightmap_export() {
+    let heightmap = Heightmap::new(0, 0, 2, 1, vec![64.0, -1.5]).expect("2 heights fit in 2x1");
+
+    let mut raw = Vec::new();
+    heightmap
+        .write_raw(&mut raw)
+        .expect("writing to a vec works");
+    assert_eq!(
+        raw,
+        [64.0f32.to_le_bytes(), (-1.5f32).to_le_bytes()].concat()
+    );
+
+    #[cfg(feature = "image")]
+    {
+        let img = heightmap.to_gray16_image(-1.5, 64.0);
+        assert_eq!(img.into_raw(), vec![u16::MAX, 0]);
+
+        let img = heightmap.to_gray16_image(0.0, 128.0);
+        assert_eq!(img.into_raw(), vec![32768, 0]);
+    }
+}
+
+#[test]
+#[cfg_attr(miri, ignore)]
+#[cfg(feature = "image")]
+fn heightmap_image_rows_are_not_mixed() {
+    let seed = -4804349703814383506;
+    let generator = Generator::new(
+        MCVersion::MC_1_21_WD,
+        seed,
+        Dimension::DIM_OVERWORLD,
+        super::GeneratorFlags::empty(),
+    );
+    let noise = BiomeNoise::new(MCVersion::MC_1_21_WD, Dimension::DIM_OVERWORLD, seed);
+
+    // A wide area, as rows were mixed up when size_x != size_z
+    let heightmap = generator
+        .heightmap(-20, 10, 40, 10, &noise)
+        .expect("the overworld has heights");
+    let img = generator
+        .generate_heightmap_image(-20, 10, 40, 10, -64.0, 320.0, &noise)
+        .expect("the overworld has heights");
+
+    assert_eq!(img.dimensions(), (40, 10));
+
+    // Each point is generated on its own, so it can't be mixed up with
+    // another row
+    for (x, z) in [(0, 0), (39, 0), (5, 9), (39, 9), (17, 3)] {
+        let expected = generator
+            .approx_surface_noise(-20 + x as i32, 10 + z as i32, 1, 1, &noise)
+            .expect("the overworld has heights")[0];
+
+        assert_eq!(heightmap.get(x, z), Some(expected));
+        assert_eq!(
+            img.get_pixel(x, z).0,
+            [super::float_between(expected, -64.0, 320.0)]
+        );
+    }
+}
+
+#[test]
+#[cfg_attr(miri, ignore)]
+fn biome_counts_cover_cache() {
+    let generator = Generator::new(
+        MCVersion::MC_1_21_WD,
+        -4804349703814383506,
+        Dimension::DIM_OVERWORLD,
+        super::GeneratorFlags::empty(),
+    );
+
+    let range = Range {
+        scale: Scale::Chunk,
+        x: -20,
+        z: 5,
+        size_x: 40,
+        size_z: 30,
+        y: 16,
+        size_y: 0,
+    };
+
+    let cache = Cache::new(&generator, range).expect("1.21 generates 1:16");
+    let counts = cache.biome_counts().expect("the biomes are valid");
+
+    assert_eq!(counts.iter().map(|(_, count)| count).sum::<usize>(), 1200);
+    assert!(counts.windows(2).all(|pair| pair[0].1 >= pair[1].1));
+
+    for (biome, count) in counts {
+        assert_eq!(
+            cache
+                .as_vec()
+                .iter()
+                .take(1200)
+                .filter(|raw| **raw == biome as i32)
+                .count(),
+            count
+        );
+    }
+}
+
+#[test]
+fn zeroed_generator_stays_in_its_box() {
+    let generator = Generator::zeroed();
+    // SAFETY: The pointer is only compared, never dereferenced
+    let address = unsafe { generator.as_ptr() } as usize;
+
+    let generator = std::thread::spawn(move || generator)
+        .join()
+        .expect("the thread doesn't panic");
+
+    // Moving the generator only moves the box, not the cubiomes generator
+    // which has pointers into itself
+    // SAFETY: The pointer is only compared, never dereferenced
+    assert_eq!(unsafe { generator.as_ptr() } as usize, address);
+    assert_eq!(
+        format!("{generator:?}"),
+        "Generator { mc: 0, dim: 0, seed: 0, flags: 0, .. }"
+    );
+}
@@ -14,18 +14,33 @@
 //! - For biome generation see [crate::generator]
 //! - For biome colormaps see [crate::colors]
 //! - For structure geneartion see [crate::structures]
+//! - For features of the end dimension see [crate::end]
 //! - For raw noise see [crate::noise]
+//! - For chunk population and carver seeds see [crate::population]
+//! - For world, structure and sister seeds see [crate::seed]
+//! - For java and xoroshiro random number generators see [crate::rng]
+//! - For rendering shaded relief maps, structure markers and legends see
+//!   [crate::render]
+//! - For rendering maps as tiles for web maps see `tiles`, which requires
+//!   crate feature tiles
+//!
+//! Errors of every module convert into [`error::Error`], which can carry the
+//! seed, version, dimension and range of the failed request.
 //!
 //! # Features
-//! This create provides the following features, which are all enabled by
-//! default:
+//! This create provides the following features, `image` and `cc_build` are
+//! enabled by default:
 //! - `image`: Provides the option to generate images from biome areas using the
 //!   [image](https://docs.rs/image/latest/image/) crate. Also see
-//!   [generator::Cache::to_image()] for generating the images.
+//!   [generator::Cache::to_image()] for generating the images, and
+//!   [render] for more detailed maps.
 //!
 //! - `cc_build`: Build cubiomes using the [cc](https://docs.rs/cc/latest/cc/)
 //!   crate instead of make. It's benchamrked to be faster and might support
 //!   more platforms than using make.
+//!
+//! - `tiles`: Rendering maps as PNG tiles for web maps in the `tiles` module.
+//!   Enables `image` and PNG support of the image crate.
 
 #![deny(clippy::ptr_cast_constness)]
 #![warn(clippy::undocumented_unsafe_blocks)]
@@ -37,11 +52,21 @@
 #![warn(clippy::missing_panics_doc)]
 
 pub use cubiomes_sys::enums;
+pub use error::Error;
 
 pub mod colors;
+pub mod end;
+pub mod error;
 pub mod generator;
 pub mod noise;
+pub mod population;
+#[cfg(feature = "image")]
+pub mod render;
+pub mod rng;
+pub mod seed;
 pub mod structures;
+#[cfg(feature = "tiles")]
+pub mod tiles;
 
 #[cfg(test)]
 mod tests;
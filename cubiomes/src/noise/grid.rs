@@ -0,0 +1,318 @@
+//! Sampling noise over an area
+
+use crate::generator::{Range, Scale};
+
+use super::SurfaceNoiseRelease;
+
+/// An area of block positions for sampling noise
+///
+/// The area starts from `x`, `y`, `z` and has `size_x` by `size_y` by `size_z`
+/// points. Horizontally the points are `scale` blocks apart and vertically
+/// `scale_y` blocks apart.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct NoiseArea {
+    /// Block x of the first point
+    pub x: i32,
+    /// Block y of the first point
+    pub y: i32,
+    /// Block z of the first point
+    pub z: i32,
+    /// Amount of points along x
+    pub size_x: u32,
+    /// Amount of points along y, one for a flat area
+    pub size_y: u32,
+    /// Amount of points along z
+    pub size_z: u32,
+    /// Distance between points along x and z in blocks
+    pub scale: u32,
+    /// Distance between points along y in blocks
+    pub scale_y: u32,
+}
+
+impl NoiseArea {
+    /// Creates a flat area at height `y` with one point per block
+    #[must_use]
+    pub const fn flat(x: i32, y: i32, z: i32, size_x: u32, size_z: u32) -> Self {
+        Self {
+            x,
+            y,
+            z,
+            size_x,
+            size_y: 1,
+            size_z,
+            scale: 1,
+            scale_y: 1,
+        }
+    }
+
+    /// Gets the amount of points in the area
+    #[must_use]
+    pub const fn len(&self) -> usize {
+        self.size_x as usize * self.size_y as usize * self.size_z as usize
+    }
+
+    /// Checks if the area has no points
+    #[must_use]
+    pub const fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Gets the block position of the point at the local coordinates
+    fn block_position(&self, x: u32, y: u32, z: u32) -> (i32, i32, i32) {
+        (
+            self.x.wrapping_add((x * self.scale) as i32),
+            self.y.wrapping_add((y * self.scale_y) as i32),
+            self.z.wrapping_add((z * self.scale) as i32),
+        )
+    }
+}
+
+/// Converts the range into the block positions it covers
+///
+/// Like biome generation, y is at a 1:4 scale unless the range is at
+/// [`Scale::Block`].
+impl From<Range> for NoiseArea {
+    fn from(range: Range) -> Self {
+        let scale = range.scale as i32;
+        let scale_y = match range.scale {
+            Scale::Block => 1,
+            _ => 4,
+        };
+
+        Self {
+            x: range.x.wrapping_mul(scale),
+            y: range.y.wrapping_mul(scale_y),
+            z: range.z.wrapping_mul(scale),
+            size_x: range.size_x,
+            size_y: range.size_y.max(1),
+            size_z: range.size_z,
+            scale: scale as u32,
+            scale_y: scale_y as u32,
+        }
+    }
+}
+
+/// Noise values sampled over a [`NoiseArea`]
+///
+/// The values are stored like in a [`crate::generator::Cache`], with x
+/// changing fastest, then z and then y.
+#[derive(Debug, Clone, PartialEq)]
+pub struct NoiseGrid {
+    area: NoiseArea,
+    values: Vec<f64>,
+}
+
+/// Minimum, maximum and mean of the values in a [`NoiseGrid`]
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct NoiseStats {
+    /// The smallest value
+    pub min: f64,
+    /// The largest value
+    pub max: f64,
+    /// The mean of the values
+    pub mean: f64,
+}
+
+/// How noise values are mapped to colors in images
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Normalization {
+    /// Maps the minimum of the grid to black and the maximum to white
+    MinMax,
+    /// Maps `min` to black and `max` to white, clamping values outside
+    Fixed {
+        /// The value mapped to black
+        min: f64,
+        /// The value mapped to white
+        max: f64,
+    },
+}
+
+impl NoiseGrid {
+    /// Samples a grid by calling `sample` with the block position of each
+    /// point in the area
+    pub fn from_fn(area: NoiseArea, mut sample: impl FnMut(i32, i32, i32) -> f64) -> Self {
+        let mut values = Vec::with_capacity(area.len());
+
+        for y in 0..area.size_y {
+            for z in 0..area.size_z {
+                for x in 0..area.size_x {
+                    let (block_x, block_y, block_z) = area.block_position(x, y, z);
+                    values.push(sample(block_x, block_y, block_z));
+                }
+            }
+        }
+
+        Self { area, values }
+    }
+
+    /// Gets the area of the grid
+    #[must_use]
+    pub fn area(&self) -> &NoiseArea {
+        &self.area
+    }
+
+    /// Gets all the values of the grid
+    #[must_use]
+    pub fn values(&self) -> &[f64] {
+        &self.values
+    }
+
+    /// Gets the value at the local coordinates, or [`None`] if they are
+    /// outside the grid
+    #[must_use]
+    pub fn get(&self, x: u32, y: u32, z: u32) -> Option<f64> {
+        if x >= self.area.size_x || y >= self.area.size_y || z >= self.area.size_z {
+            return None;
+        }
+
+        let index = (y as usize * self.area.size_z as usize + z as usize)
+            * self.area.size_x as usize
+            + x as usize;
+
+        self.values.get(index).copied()
+    }
+
+    /// Gets the horizontal layer at local `y`, or [`None`] if it is outside the
+    /// grid
+    #[must_use]
+    pub fn layer(&self, y: u32) -> Option<&[f64]> {
+        let layer_len = self.area.size_x as usize * self.area.size_z as usize;
+        let start = y as usize * layer_len;
+
+        self.values.get(start..start + layer_len)
+    }
+
+    /// Calculates the minimum, maximum and mean of the values
+    ///
+    /// Returns [`None`] if the grid is empty.
+    #[must_use]
+    pub fn stats(&self) -> Option<NoiseStats> {
+        stats(&self.values)
+    }
+
+    /// Generates a grayscale image of the horizontal layer at local `y`
+    ///
+    /// This function requires crate feature image
+    ///
+    /// Returns [`None`] if the layer is outside the grid.
+    ///
+    /// # Examples
+    /// ```
+    #[doc = include_str!("../../examples/sample_raw_noise.rs")]
+    /// ```
+    #[cfg(feature = "image")]
+    #[must_use]
+    pub fn to_gray_image(&self, y: u32, normalization: Normalization) -> Option<image::GrayImage> {
+        use image::GrayImage;
+
+        let layer = self.layer(y)?;
+        let normalize = self.normalizer(layer, normalization);
+
+        Some(GrayImage::from_fn(
+            self.area.size_x,
+            self.area.size_z,
+            |x, z| {
+                let value = normalize(layer[z as usize * self.area.size_x as usize + x as usize]);
+                [(value * u8::MAX as f64).round() as u8].into()
+            },
+        ))
+    }
+
+    /// Generates a colored image of the horizontal layer at local `y`
+    ///
+    /// This function requires crate feature image
+    ///
+    /// The values are normalized to `0.0..=1.0` and then mapped to a color
+    /// with `color_map`. Returns [`None`] if the layer is outside the grid.
+    ///
+    /// # Examples
+    /// ```
+    /// use cubiomes::noise::{DoublePerlinNoise, NoiseArea, NoiseSampler, Normalization};
+    /// use cubiomes::rng::Xoroshiro;
+    ///
+    /// let noise = DoublePerlinNoise::from_xoroshiro(&mut Xoroshiro::new(5), -9, &[1.0, 1.0])
+    ///     .expect("the octaves are supported");
+    ///
+    /// let grid = noise.sample_grid(NoiseArea::flat(0, 0, 0, 64, 64));
+    ///
+    /// // Blue for low values and red for high values
+    /// let img = grid
+    ///     .to_color_image(0, Normalization::Fixed { min: -1.0, max: 1.0 }, |value| {
+    ///         [(value * 255.0) as u8, 0, ((1.0 - value) * 255.0) as u8].into()
+    ///     })
+    ///     .expect("the grid has a layer 0");
+    ///
+    /// assert_eq!(img.dimensions(), (64, 64));
+    /// ```
+    #[cfg(feature = "image")]
+    #[must_use]
+    pub fn to_color_image(
+        &self,
+        y: u32,
+        normalization: Normalization,
+        color_map: impl Fn(f64) -> image::Rgb<u8>,
+    ) -> Option<image::RgbImage> {
+        use image::RgbImage;
+
+        let layer = self.layer(y)?;
+        let normalize = self.normalizer(layer, normalization);
+
+        Some(RgbImage::from_fn(
+            self.area.size_x,
+            self.area.size_z,
+            |x, z| {
+                color_map(normalize(
+                    layer[z as usize * self.area.size_x as usize + x as usize],
+                ))
+            },
+        ))
+    }
+
+    /// Creates a function mapping values of the layer to `0.0..=1.0`
+    #[cfg(feature = "image")]
+    fn normalizer(&self, layer: &[f64], normalization: Normalization) -> impl Fn(f64) -> f64 {
+        let (min, max) = match normalization {
+            Normalization::MinMax => {
+                stats(layer).map_or((0.0, 1.0), |stats| (stats.min, stats.max))
+            }
+            Normalization::Fixed { min, max } => (min, max),
+        };
+
+        move |value| {
+            let normalized = (value - min) / (max - min);
+
+            if normalized.is_nan() {
+                0.0
+            } else {
+                normalized.clamp(0.0, 1.0)
+            }
+        }
+    }
+}
+
+fn stats(values: &[f64]) -> Option<NoiseStats> {
+    if values.is_empty() {
+        return None;
+    }
+
+    let (min, max, sum) = values.iter().fold(
+        (f64::INFINITY, f64::NEG_INFINITY, 0.0),
+        |(min, max, sum), value| (min.min(*value), max.max(*value), sum + value),
+    );
+
+    Some(NoiseStats {
+        min,
+        max,
+        mean: sum / values.len() as f64,
+    })
+}
+
+impl SurfaceNoiseRelease {
+    /// Samples the underlying noise at every point of the area
+    ///
+    /// This is the same as calling [`Self::sample_raw()`] for every point.
+    #[must_use]
+    pub fn sample_grid(&self, area: NoiseArea) -> NoiseGrid {
+        NoiseGrid::from_fn(area, |x, y, z| self.sample_raw(x, y, z))
+    }
+}
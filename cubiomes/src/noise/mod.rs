@@ -0,0 +1,263 @@
+//! Module for functions dealing with raw noise
+//!
+//! Unless you are doing something advanced, this module is only used for
+//! initializing noise used in heightmap generation.
+//!
+//! For reproducing other noises of minecraft, the building blocks
+//! [`PerlinNoise`], [`OctaveNoise`] and [`DoublePerlinNoise`] can be seeded
+//! from a [`crate::rng::JavaRandom`] or [`crate::rng::Xoroshiro`] and sampled
+//! through [`NoiseSampler`].
+//!
+//! # Usage
+//! Sampling the raw noise
+//! ```
+#![doc = include_str!("../../examples/sample_raw_noise.rs")]
+//! ```
+//!
+//! Sampling a 1.18+ style climate noise
+//! ```
+//! use cubiomes::noise::{DoublePerlinNoise, NoiseSampler};
+//! use cubiomes::rng::Xoroshiro;
+//!
+//! let mut rng = Xoroshiro::new(-4804349703814383506);
+//! let noise = DoublePerlinNoise::from_xoroshiro(&mut rng, -10, &[1.5, 0.0, 1.0, 0.0, 0.0, 0.0])
+//!     .expect("the octaves are supported");
+//!
+//! let mut buffer = vec![0.0; 16 * 16];
+//! noise.sample_area_into(&mut buffer, 0.0, 0.0, 0.0, 16, 4.0);
+//!
+//! assert_eq!(buffer[17], noise.sample(4.0, 0.0, 4.0));
+//! ```
+
+use cubiomes_sys::{
+    approxSurfaceBeta,
+    enums::{Dimension, MCVersion},
+    initSurfaceNoise, initSurfaceNoiseBeta,
+};
+
+use thiserror::Error;
+
+use crate::generator::{error::GeneratorError, Generator};
+
+mod grid;
+mod perlin;
+pub use grid::*;
+pub use perlin::*;
+
+#[cfg(test)]
+mod tests;
+
+/// An error initializing noise
+#[derive(Error, Debug, PartialEq, Eq, Hash, Clone, Copy)]
+pub enum NoiseError {
+    /// The octaves are not supported
+    ///
+    /// Which octaves are supported depends on the random used to initialize
+    /// the noise.
+    #[error("{count} octaves starting from octave {first_octave} are not supported")]
+    InvalidOctaves {
+        /// The first octave
+        first_octave: i32,
+        /// The amount of octaves
+        count: usize,
+    },
+    /// Every amplitude is zero, so there are no octaves
+    #[error("all octave amplitudes are zero")]
+    NoAmplitudes,
+}
+
+/// This enum represents any surfacenoise.
+///
+/// See each surfance noise respectively for its usage:
+/// - [SurfaceNoiseRelease]
+/// - [SurfaceNoiseBeta]
+#[derive(Debug)]
+pub enum BiomeNoise {
+    /// A release (post 1.0) biomenoise
+    Release(SurfaceNoiseRelease),
+    /// A beta minecraft biomenoise
+    Beta(SurfaceNoiseBeta),
+}
+
+impl BiomeNoise {
+    /// Initializes the surface noise used by the given version
+    ///
+    /// Beta 1.7 and older use [SurfaceNoiseBeta], which only exists for the
+    /// overworld. All later versions use [SurfaceNoiseRelease].
+    pub fn new(version: MCVersion, dimension: Dimension, seed: i64) -> Self {
+        if version <= MCVersion::MC_B1_7 {
+            Self::Beta(SurfaceNoiseBeta::new(seed))
+        } else {
+            Self::Release(SurfaceNoiseRelease::new(dimension, seed))
+        }
+    }
+}
+
+impl From<SurfaceNoiseRelease> for BiomeNoise {
+    fn from(value: SurfaceNoiseRelease) -> Self {
+        Self::Release(value)
+    }
+}
+
+impl From<SurfaceNoiseBeta> for BiomeNoise {
+    fn from(value: SurfaceNoiseBeta) -> Self {
+        Self::Beta(value)
+    }
+}
+
+/// Represents surfacenoise from release (post 1.0) minecraft.
+///
+/// The noise is boxed, as it contains pointers into itself.
+#[derive(Debug)]
+pub struct SurfaceNoiseRelease(Box<cubiomes_sys::SurfaceNoise>);
+
+/// Represents surfacenoise from beta minecraft.
+///
+/// The noise is boxed, as it contains pointers into itself.
+#[derive(Debug)]
+pub struct SurfaceNoiseBeta(Box<cubiomes_sys::SurfaceNoiseBeta>);
+
+impl SurfaceNoiseRelease {
+    /// Initializes a new [self] for the given seed and [Dimension]
+    pub fn new(dimension: Dimension, seed: i64) -> Self {
+        let mut noise = Box::<cubiomes_sys::SurfaceNoise>::new_zeroed();
+
+        // SAFETY: Arguments to foregin function are correct. All zeroes is a
+        // valid surface noise, as it only consists of numbers and pointers, and
+        // initSurfaceNoise initializes it.
+        unsafe {
+            initSurfaceNoise(noise.as_mut_ptr(), dimension as i32, seed as u64);
+            Self(noise.assume_init())
+        }
+    }
+
+    /// Samples the underlying noise
+    pub fn sample_raw(&self, x: i32, y: i32, z: i32) -> f64 {
+        // SAFETY: The foreign function arguments are correct
+        unsafe { cubiomes_sys::sampleSurfaceNoise(self.as_ptr(), x, y, z) }
+    }
+
+    /// Samples the underlying noise, with min and max being minimum and maximum
+    /// noise.
+    pub fn sample_between(&self, x: i32, y: i32, z: i32, min: f64, max: f64) -> f64 {
+        // SAFETY: The foreign function arguments are correct.
+        unsafe { cubiomes_sys::sampleSurfaceNoiseBetween(self.as_ptr(), x, y, z, min, max) }
+    }
+
+    /// Gets the underlying pointer inside [self].
+    ///
+    /// This function is mostly provided for use with cubiomes_sys.
+    ///
+    /// # Safety
+    /// Mutating the underlying data using this pointer is unsafe. Instead use
+    /// [`Self::as_mut_ptr()`] if you need mutable access.
+    ///
+    /// The pointer becomes dangling if [self] is droppped.
+    pub unsafe fn as_ptr(&self) -> *const cubiomes_sys::SurfaceNoise {
+        &*self.0
+    }
+
+    /// Gets the underlying pointer inside [self].
+    ///
+    /// This function is mostly provided for use with cubiomes_sys.
+    ///
+    /// # Safety
+    /// The pointer becomes dangling if [self] is droppped.
+    pub unsafe fn as_mut_ptr(&mut self) -> *mut cubiomes_sys::SurfaceNoise {
+        &mut *self.0
+    }
+}
+
+// SAFETY: As interior mutation should not happen, the type is both send and
+// sync
+unsafe impl Send for SurfaceNoiseRelease {}
+// SAFETY: As interior mutation should not happen, the type is both send and
+// sync
+unsafe impl Sync for SurfaceNoiseRelease {}
+
+impl SurfaceNoiseBeta {
+    /// Initializes a new surface noise.
+    pub fn new(seed: i64) -> Self {
+        let mut noise = Box::<cubiomes_sys::SurfaceNoiseBeta>::new_zeroed();
+
+        // SAFETY: Arguments to foreign function are correct. All zeroes is a
+        // valid surface noise, as it only consists of numbers and pointers, and
+        // initSurfaceNoiseBeta initializes it.
+        unsafe {
+            initSurfaceNoiseBeta(noise.as_mut_ptr(), seed as u64);
+            Self(noise.assume_init())
+        }
+    }
+
+    /// Approximates the surface height at a block position
+    ///
+    /// Beta terrain depends on the climate, so the biome noise of the
+    /// generator is sampled together with this noise. The generator should
+    /// have the same seed as this noise.
+    ///
+    /// # Errors
+    /// Returns [`GeneratorError::UnsupportedVersion`] if the generator is not
+    /// for beta 1.7 or older and [`GeneratorError::WrongDimension`] if it isn't
+    /// set to the overworld.
+    pub fn approx_surface(
+        &self,
+        generator: &Generator,
+        x: i32,
+        z: i32,
+    ) -> Result<f64, GeneratorError> {
+        let version = generator.minecraft_version()?;
+        if version > MCVersion::MC_B1_7 {
+            return Err(GeneratorError::UnsupportedVersion(version));
+        }
+
+        let dimension = generator.dimension()?;
+        if dimension != Dimension::DIM_OVERWORLD {
+            return Err(GeneratorError::WrongDimension {
+                expected: Dimension::DIM_OVERWORLD,
+                actual: dimension,
+            });
+        }
+
+        // SAFETY: The generator is a beta overworld generator, so its biome
+        // noise is the beta biome noise, which has been initialized. The
+        // surface noise was initialized in the constructor.
+        Ok(unsafe {
+            approxSurfaceBeta(
+                &(*generator.as_ptr()).__bindgen_anon_1.__bindgen_anon_3.bnb,
+                self.as_ptr(),
+                x,
+                z,
+            )
+        })
+    }
+
+    /// Gets the underlying pointer inside [self].
+    ///
+    /// This function is mostly provided for use with cubiomes_sys.
+    ///
+    /// # Safety
+    /// Mutating the underlying data using this pointer is unsafe. Instead use
+    /// [`Self::as_mut_ptr()`] if you need mutable access.
+    ///
+    /// The pointer becomes dangling if [self] is droppped.
+    pub unsafe fn as_ptr(&self) -> *const cubiomes_sys::SurfaceNoiseBeta {
+        &*self.0
+    }
+
+    /// Gets the underlying pointer inside [self].
+    ///
+    /// This function is mostly provided for use with cubiomes_sys.
+    ///
+    /// # Safety
+    /// The pointer becomes dangling if [self] is droppped.
+    pub unsafe fn as_mut_ptr(&mut self) -> *mut cubiomes_sys::SurfaceNoiseBeta {
+        &mut *self.0
+    }
+}
+
+// SAFETY: As interior mutation does not happen, the type is both send and
+// sync
+unsafe impl Send for SurfaceNoiseBeta {}
+// SAFETY: As interior mutation does not happen, the type is both send and
+// sync
+unsafe impl Sync for SurfaceNoiseBeta {}
@@ -0,0 +1,455 @@
+//! Perlin, octave and double perlin noise
+
+use std::{mem::MaybeUninit, ptr};
+
+use cubiomes_sys::{
+    doublePerlinInit, octaveInit, perlinInit, sampleDoublePerlin, sampleOctave, samplePerlin,
+    sampleSimplex2D, xDoublePerlinInit, xOctaveInit, xPerlinInit,
+};
+
+use super::{NoiseArea, NoiseError, NoiseGrid};
+use crate::rng::{JavaRandom, Xoroshiro};
+
+/// Shared interface for sampling the different kinds of noise
+pub trait NoiseSampler {
+    /// Samples the noise at a point
+    fn sample(&self, x: f64, y: f64, z: f64) -> f64;
+
+    /// Samples the noise at a point of the horizontal plane at y 0
+    fn sample_2d(&self, x: f64, z: f64) -> f64 {
+        self.sample(x, 0.0, z)
+    }
+
+    /// Samples a horizontal area at height `y` into `buffer`
+    ///
+    /// The area starts from `x`, `z` and has `size_x` points per row, which
+    /// are `step` apart. Rows are stored one after another with x changing
+    /// fastest, and as many rows are sampled as fit in the buffer.
+    ///
+    /// # Panics
+    /// Panics if `size_x` is zero
+    fn sample_area_into(
+        &self,
+        buffer: &mut [f64],
+        x: f64,
+        y: f64,
+        z: f64,
+        size_x: usize,
+        step: f64,
+    ) {
+        assert!(size_x > 0, "size_x must not be zero");
+
+        for (row, line) in buffer.chunks_mut(size_x).enumerate() {
+            let sample_z = z + row as f64 * step;
+
+            for (column, value) in line.iter_mut().enumerate() {
+                *value = self.sample(x + column as f64 * step, y, sample_z);
+            }
+        }
+    }
+
+    /// Samples the noise at every point of the area
+    ///
+    /// The block positions of the points are used as the noise coordinates.
+    fn sample_grid(&self, area: NoiseArea) -> NoiseGrid {
+        NoiseGrid::from_fn(area, |x, y, z| self.sample(x as f64, y as f64, z as f64))
+    }
+}
+
+/// A single perlin noise
+///
+/// This is the building block of the other noises, but is also used by itself
+/// for example by the ocean temperature of pre 1.18 versions.
+#[derive(Debug, Clone, Copy)]
+pub struct PerlinNoise(cubiomes_sys::PerlinNoise);
+
+impl PerlinNoise {
+    /// Initializes the noise from a java random, advancing it like minecraft
+    /// does
+    pub fn from_java_random(rng: &mut JavaRandom) -> Self {
+        let mut noise = MaybeUninit::<cubiomes_sys::PerlinNoise>::zeroed();
+
+        // SAFETY: All zeroes is a valid perlin noise, as it only consists of
+        // numbers, and perlinInit initializes it.
+        unsafe {
+            perlinInit(noise.as_mut_ptr(), rng.state_mut());
+            Self(noise.assume_init())
+        }
+    }
+
+    /// Initializes the noise from a xoroshiro random, advancing it like
+    /// minecraft does
+    pub fn from_xoroshiro(rng: &mut Xoroshiro) -> Self {
+        let mut noise = MaybeUninit::<cubiomes_sys::PerlinNoise>::zeroed();
+
+        // SAFETY: All zeroes is a valid perlin noise, as it only consists of
+        // numbers, and xPerlinInit initializes it.
+        unsafe {
+            rng.with_raw(|raw| xPerlinInit(noise.as_mut_ptr(), raw));
+            Self(noise.assume_init())
+        }
+    }
+
+    /// Samples the 2D simplex noise of the perlin noise, which is used by the
+    /// end islands
+    pub fn sample_simplex(&self, x: f64, z: f64) -> f64 {
+        // SAFETY: The noise was initialized in the constructor
+        unsafe { sampleSimplex2D(&self.0, x, z) }
+    }
+
+    /// Gets the underlying pointer inside self.
+    ///
+    /// This function is mostly provided for use with cubiomes_sys.
+    ///
+    /// # Safety
+    /// Mutating the underlying data using this pointer is unsafe. Instead use
+    /// [`Self::as_mut_ptr()`] if you need mutable access.
+    ///
+    /// The pointer becomes dangling if self is dropped or moved.
+    pub unsafe fn as_ptr(&self) -> *const cubiomes_sys::PerlinNoise {
+        &self.0
+    }
+
+    /// Gets the underlying pointer inside self.
+    ///
+    /// This function is mostly provided for use with cubiomes_sys.
+    ///
+    /// # Safety
+    /// The pointer becomes dangling if self is dropped or moved.
+    pub unsafe fn as_mut_ptr(&mut self) -> *mut cubiomes_sys::PerlinNoise {
+        &mut self.0
+    }
+}
+
+impl NoiseSampler for PerlinNoise {
+    fn sample(&self, x: f64, y: f64, z: f64) -> f64 {
+        // SAFETY: The noise was initialized in the constructor
+        unsafe { samplePerlin(&self.0, x, y, z, 0.0, 0.0) }
+    }
+}
+
+/// Several perlin noises, or octaves, summed together
+///
+/// Each octave has double the frequency and half the amplitude of the
+/// previous one. The octaves are numbered so that octave 0 has a frequency of
+/// one, octave -1 a frequency of one half, and so on.
+#[derive(Debug)]
+pub struct OctaveNoise {
+    noise: cubiomes_sys::OctaveNoise,
+    // The noise points into the octaves
+    octaves: Box<[cubiomes_sys::PerlinNoise]>,
+}
+
+impl OctaveNoise {
+    /// Initializes `count` octaves, starting from `first_octave`, from a java
+    /// random, advancing it like minecraft does
+    ///
+    /// # Errors
+    /// Returns [`NoiseError::InvalidOctaves`] if `count` is zero or the
+    /// octaves aren't within -61..=0.
+    pub fn from_java_random(
+        rng: &mut JavaRandom,
+        first_octave: i32,
+        count: usize,
+    ) -> Result<Self, NoiseError> {
+        validate_octaves(first_octave, count, -61)?;
+
+        let mut octaves = Box::<[cubiomes_sys::PerlinNoise]>::new_zeroed_slice(count);
+        let mut noise = empty_octave_noise();
+
+        // SAFETY: The octave range was validated above, so octaveInit
+        // initializes exactly `count` octaves. All zeroes is a valid perlin
+        // noise.
+        let octaves = unsafe {
+            octaveInit(
+                &mut noise,
+                rng.state_mut(),
+                octaves.as_mut_ptr().cast(),
+                first_octave,
+                count as i32,
+            );
+            octaves.assume_init()
+        };
+
+        Ok(Self { noise, octaves })
+    }
+
+    /// Initializes octaves starting from `first_octave` from a xoroshiro
+    /// random, advancing it like minecraft does
+    ///
+    /// There is one amplitude per octave, and octaves with an amplitude of
+    /// zero are skipped.
+    ///
+    /// # Errors
+    /// Returns [`NoiseError::InvalidOctaves`] if there are no amplitudes or
+    /// more than 9 of them, or the octaves aren't within -12..=0, and
+    /// [`NoiseError::NoAmplitudes`] if every amplitude is zero.
+    pub fn from_xoroshiro(
+        rng: &mut Xoroshiro,
+        first_octave: i32,
+        amplitudes: &[f64],
+    ) -> Result<Self, NoiseError> {
+        let count = validate_xoroshiro_octaves(first_octave, amplitudes)?;
+
+        let mut octaves = Box::<[cubiomes_sys::PerlinNoise]>::new_zeroed_slice(count);
+        let mut noise = empty_octave_noise();
+
+        // SAFETY: The octave range was validated above, and xOctaveInit
+        // initializes one octave per non zero amplitude. All zeroes is a valid
+        // perlin noise.
+        let octaves = unsafe {
+            rng.with_raw(|raw| {
+                xOctaveInit(
+                    &mut noise,
+                    raw,
+                    octaves.as_mut_ptr().cast(),
+                    amplitudes.as_ptr(),
+                    first_octave,
+                    amplitudes.len() as i32,
+                    -1,
+                )
+            });
+            octaves.assume_init()
+        };
+
+        Ok(Self { noise, octaves })
+    }
+
+    /// Gets the amount of octaves in the noise
+    #[must_use]
+    pub fn octave_count(&self) -> usize {
+        self.octaves.len()
+    }
+
+    /// Gets the underlying pointer inside self.
+    ///
+    /// This function is mostly provided for use with cubiomes_sys.
+    ///
+    /// # Safety
+    /// Mutating the underlying data using this pointer is unsafe.
+    ///
+    /// The pointer becomes dangling if self is dropped or moved.
+    pub unsafe fn as_ptr(&self) -> *const cubiomes_sys::OctaveNoise {
+        &self.noise
+    }
+}
+
+impl Clone for OctaveNoise {
+    fn clone(&self) -> Self {
+        let mut clone = Self {
+            noise: self.noise,
+            octaves: self.octaves.clone(),
+        };
+        clone.noise.octaves = clone.octaves.as_mut_ptr();
+
+        clone
+    }
+}
+
+impl NoiseSampler for OctaveNoise {
+    fn sample(&self, x: f64, y: f64, z: f64) -> f64 {
+        // SAFETY: The noise was initialized in the constructor and points into
+        // the octaves, which live as long as self.
+        unsafe { sampleOctave(&self.noise, x, y, z) }
+    }
+}
+
+// SAFETY: The pointer inside the noise only points into the octaves owned by
+// self, and interior mutation does not happen
+unsafe impl Send for OctaveNoise {}
+// SAFETY: The pointer inside the noise only points into the octaves owned by
+// self, and interior mutation does not happen
+unsafe impl Sync for OctaveNoise {}
+
+/// Two octave noises sampled at slightly different positions and summed
+///
+/// This is the noise used by the climate parameters of 1.18+ biome
+/// generation.
+#[derive(Debug)]
+pub struct DoublePerlinNoise {
+    noise: cubiomes_sys::DoublePerlinNoise,
+    // The octave noises of the noise point into the octaves
+    octaves: Box<[cubiomes_sys::PerlinNoise]>,
+}
+
+impl DoublePerlinNoise {
+    /// Initializes two octave noises with `count` octaves starting from
+    /// `first_octave` from a java random, advancing it like minecraft does
+    ///
+    /// # Errors
+    /// Returns [`NoiseError::InvalidOctaves`] if `count` is zero or the
+    /// octaves aren't within -61..=0.
+    pub fn from_java_random(
+        rng: &mut JavaRandom,
+        first_octave: i32,
+        count: usize,
+    ) -> Result<Self, NoiseError> {
+        validate_octaves(first_octave, count, -61)?;
+
+        let mut octaves = Box::<[cubiomes_sys::PerlinNoise]>::new_zeroed_slice(count * 2);
+        let mut noise = cubiomes_sys::DoublePerlinNoise {
+            amplitude: 0.0,
+            octA: empty_octave_noise(),
+            octB: empty_octave_noise(),
+        };
+
+        // SAFETY: The octave range was validated above, so doublePerlinInit
+        // initializes exactly `count` octaves in both halves of the octaves.
+        // All zeroes is a valid perlin noise.
+        let octaves = unsafe {
+            let first_half = octaves.as_mut_ptr().cast();
+            doublePerlinInit(
+                &mut noise,
+                rng.state_mut(),
+                first_half,
+                first_half.add(count),
+                first_octave,
+                count as i32,
+            );
+            octaves.assume_init()
+        };
+
+        Ok(Self { noise, octaves })
+    }
+
+    /// Initializes two octave noises starting from `first_octave` from a
+    /// xoroshiro random, advancing it like minecraft does
+    ///
+    /// There is one amplitude per octave, and octaves with an amplitude of
+    /// zero are skipped.
+    ///
+    /// # Errors
+    /// Returns [`NoiseError::InvalidOctaves`] if there are no amplitudes or
+    /// more than 9 of them, or the octaves aren't within -12..=0, and
+    /// [`NoiseError::NoAmplitudes`] if every amplitude is zero.
+    pub fn from_xoroshiro(
+        rng: &mut Xoroshiro,
+        first_octave: i32,
+        amplitudes: &[f64],
+    ) -> Result<Self, NoiseError> {
+        let count = validate_xoroshiro_octaves(first_octave, amplitudes)?;
+
+        let mut octaves = Box::<[cubiomes_sys::PerlinNoise]>::new_zeroed_slice(count * 2);
+        let mut noise = cubiomes_sys::DoublePerlinNoise {
+            amplitude: 0.0,
+            octA: empty_octave_noise(),
+            octB: empty_octave_noise(),
+        };
+
+        // SAFETY: The octave range was validated above, and xDoublePerlinInit
+        // initializes two octaves per non zero amplitude. All zeroes is a
+        // valid perlin noise.
+        let octaves = unsafe {
+            rng.with_raw(|raw| {
+                xDoublePerlinInit(
+                    &mut noise,
+                    raw,
+                    octaves.as_mut_ptr().cast(),
+                    amplitudes.as_ptr(),
+                    first_octave,
+                    amplitudes.len() as i32,
+                    -1,
+                )
+            });
+            octaves.assume_init()
+        };
+
+        Ok(Self { noise, octaves })
+    }
+
+    /// Gets the amplitude the sum of the octave noises is scaled by
+    #[must_use]
+    pub fn amplitude(&self) -> f64 {
+        self.noise.amplitude
+    }
+
+    /// Gets the underlying pointer inside self.
+    ///
+    /// This function is mostly provided for use with cubiomes_sys.
+    ///
+    /// # Safety
+    /// Mutating the underlying data using this pointer is unsafe.
+    ///
+    /// The pointer becomes dangling if self is dropped or moved.
+    pub unsafe fn as_ptr(&self) -> *const cubiomes_sys::DoublePerlinNoise {
+        &self.noise
+    }
+}
+
+impl Clone for DoublePerlinNoise {
+    fn clone(&self) -> Self {
+        let mut clone = Self {
+            noise: self.noise,
+            octaves: self.octaves.clone(),
+        };
+
+        // The second octave noise starts right after the octaves of the first
+        let first_half = clone.octaves.as_mut_ptr();
+        clone.noise.octA.octaves = first_half;
+        clone.noise.octB.octaves = first_half.wrapping_add(self.noise.octA.octcnt as usize);
+
+        clone
+    }
+}
+
+impl NoiseSampler for DoublePerlinNoise {
+    fn sample(&self, x: f64, y: f64, z: f64) -> f64 {
+        // SAFETY: The noise was initialized in the constructor and points into
+        // the octaves, which live as long as self.
+        unsafe { sampleDoublePerlin(&self.noise, x, y, z) }
+    }
+}
+
+// SAFETY: The pointers inside the noise only point into the octaves owned by
+// self, and interior mutation does not happen
+unsafe impl Send for DoublePerlinNoise {}
+// SAFETY: The pointers inside the noise only point into the octaves owned by
+// self, and interior mutation does not happen
+unsafe impl Sync for DoublePerlinNoise {}
+
+fn empty_octave_noise() -> cubiomes_sys::OctaveNoise {
+    cubiomes_sys::OctaveNoise {
+        octcnt: 0,
+        octaves: ptr::null_mut(),
+    }
+}
+
+/// Checks the octaves `first_octave..first_octave + count` are between
+/// `lowest_octave` and 0
+fn validate_octaves(first_octave: i32, count: usize, lowest_octave: i32) -> Result<(), NoiseError> {
+    let last_octave = i32::try_from(count)
+        .ok()
+        .and_then(|count| first_octave.checked_add(count - 1));
+
+    match last_octave {
+        Some(last_octave) if count > 0 && first_octave >= lowest_octave && last_octave <= 0 => {
+            Ok(())
+        }
+        _ => Err(NoiseError::InvalidOctaves {
+            first_octave,
+            count,
+        }),
+    }
+}
+
+/// Checks the octaves are supported by xoroshiro initialization, and returns
+/// the amount of octaves which will be initialized
+fn validate_xoroshiro_octaves(first_octave: i32, amplitudes: &[f64]) -> Result<usize, NoiseError> {
+    // Cubiomes has tables for octaves -12..=0 and up to 9 octaves
+    if amplitudes.len() > 9 {
+        return Err(NoiseError::InvalidOctaves {
+            first_octave,
+            count: amplitudes.len(),
+        });
+    }
+    validate_octaves(first_octave, amplitudes.len(), -12)?;
+
+    match amplitudes
+        .iter()
+        .filter(|amplitude| **amplitude != 0.0)
+        .count()
+    {
+        0 => Err(NoiseError::NoAmplitudes),
+        count => Ok(count),
+    }
+}
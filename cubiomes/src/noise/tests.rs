@@ -0,0 +1,268 @@
+use super::{
+    DoublePerlinNoise, NoiseArea, NoiseError, NoiseGrid, NoiseSampler, NoiseStats, OctaveNoise,
+    PerlinNoise, SurfaceNoiseRelease,
+};
+use crate::enums::Dimension;
+use crate::generator::{Range, Scale};
+use crate::rng::{JavaRandom, Xoroshiro};
+
+const SEED: i64 = -4804349703814383506;
+const AMPLITUDES: [f64; 6] = [1.5, 0.0, 1.0, 0.0, 0.0, 0.0];
+
+#[test]
+#[cfg_attr(miri, ignore)]
+fn noise_is_deterministic() {
+    let perlin_a = PerlinNoise::from_java_random(&mut JavaRandom::new(SEED));
+    let perlin_b = PerlinNoise::from_java_random(&mut JavaRandom::new(SEED));
+
+    let octave_a = OctaveNoise::from_xoroshiro(&mut Xoroshiro::new(SEED), -10, &AMPLITUDES)
+        .expect("octaves are supported");
+    let octave_b = OctaveNoise::from_xoroshiro(&mut Xoroshiro::new(SEED), -10, &AMPLITUDES)
+        .expect("octaves are supported");
+
+    for (x, y, z) in [(0.0, 0.0, 0.0), (12.5, 64.0, -3.25), (-1000.0, 1.0, 77.7)] {
+        assert_eq!(perlin_a.sample(x, y, z), perlin_b.sample(x, y, z));
+        assert_eq!(octave_a.sample(x, y, z), octave_b.sample(x, y, z));
+    }
+}
+
+#[test]
+#[cfg_attr(miri, ignore)]
+fn initialization_advances_rng() {
+    let mut rng = JavaRandom::new(SEED);
+    let first = PerlinNoise::from_java_random(&mut rng);
+    let second = PerlinNoise::from_java_random(&mut rng);
+
+    assert_ne!(rng, JavaRandom::new(SEED));
+    assert_ne!(first.sample(0.5, 0.5, 0.5), second.sample(0.5, 0.5, 0.5));
+
+    let mut rng = Xoroshiro::new(SEED);
+    OctaveNoise::from_xoroshiro(&mut rng, -4, &[1.0]).expect("octaves are supported");
+    assert_ne!(rng, Xoroshiro::new(SEED));
+}
+
+#[test]
+#[cfg_attr(miri, ignore)]
+fn zero_amplitudes_are_skipped() {
+    let noise = OctaveNoise::from_xoroshiro(&mut Xoroshiro::new(SEED), -10, &AMPLITUDES)
+        .expect("octaves are supported");
+    assert_eq!(noise.octave_count(), 2);
+
+    let noise = OctaveNoise::from_java_random(&mut JavaRandom::new(SEED), -7, 4)
+        .expect("octaves are supported");
+    assert_eq!(noise.octave_count(), 4);
+}
+
+#[test]
+#[cfg_attr(miri, ignore)]
+fn cloned_noise_outlives_original() {
+    let noise = DoublePerlinNoise::from_xoroshiro(&mut Xoroshiro::new(SEED), -10, &AMPLITUDES)
+        .expect("octaves are supported");
+    let expected = noise.sample(100.0, 0.0, -50.0);
+
+    let clone = noise.clone();
+    drop(noise);
+
+    assert_eq!(clone.sample(100.0, 0.0, -50.0), expected);
+
+    let noise = DoublePerlinNoise::from_java_random(&mut JavaRandom::new(SEED), -3, 3)
+        .expect("octaves are supported");
+    let expected = noise.sample(1.0, 2.0, 3.0);
+
+    let clone = noise.clone();
+    drop(noise);
+
+    assert_eq!(clone.sample(1.0, 2.0, 3.0), expected);
+}
+
+#[test]
+#[cfg_attr(miri, ignore)]
+fn area_matches_single_samples() {
+    let noise = OctaveNoise::from_java_random(&mut JavaRandom::new(SEED), -5, 5)
+        .expect("octaves are supported");
+
+    let mut buffer = vec![0.0; 7 * 5];
+    noise.sample_area_into(&mut buffer, -10.0, 3.0, 20.0, 7, 0.5);
+
+    for (i, value) in buffer.iter().enumerate() {
+        let x = -10.0 + (i % 7) as f64 * 0.5;
+        let z = 20.0 + (i / 7) as f64 * 0.5;
+
+        assert_eq!(*value, noise.sample(x, 3.0, z));
+    }
+
+    assert_eq!(noise.sample_2d(4.0, 2.0), noise.sample(4.0, 0.0, 2.0));
+}
+
+#[test]
+fn unsupported_octaves() {
+    let mut java = JavaRandom::new(SEED);
+    let mut xoroshiro = Xoroshiro::new(SEED);
+
+    assert_eq!(
+        OctaveNoise::from_java_random(&mut java, 0, 0).err(),
+        Some(NoiseError::InvalidOctaves {
+            first_octave: 0,
+            count: 0
+        })
+    );
+    assert_eq!(
+        OctaveNoise::from_java_random(&mut java, -2, 4).err(),
+        Some(NoiseError::InvalidOctaves {
+            first_octave: -2,
+            count: 4
+        })
+    );
+    assert_eq!(
+        DoublePerlinNoise::from_xoroshiro(&mut xoroshiro, -13, &[1.0]).err(),
+        Some(NoiseError::InvalidOctaves {
+            first_octave: -13,
+            count: 1
+        })
+    );
+    assert_eq!(
+        OctaveNoise::from_xoroshiro(&mut xoroshiro, -12, &[1.0; 10]).err(),
+        Some(NoiseError::InvalidOctaves {
+            first_octave: -12,
+            count: 10
+        })
+    );
+    assert_eq!(
+        DoublePerlinNoise::from_xoroshiro(&mut xoroshiro, -4, &[0.0, 0.0]).err(),
+        Some(NoiseError::NoAmplitudes)
+    );
+
+    // Failed initialization doesn't touch the rng
+    assert_eq!(java, JavaRandom::new(SEED));
+    assert_eq!(xoroshiro, Xoroshiro::new(SEED));
+}
+
+#[test]
+fn grid_is_ordered_like_cache() {
+    let area = NoiseArea {
+        x: 10,
+        y: -64,
+        z: -5,
+        size_x: 3,
+        size_y: 2,
+        size_z: 4,
+        scale: 4,
+        scale_y: 8,
+    };
+    let grid = NoiseGrid::from_fn(area, |x, y, z| (x * 1_000_000 + y * 1000 + z) as f64);
+
+    assert_eq!(grid.values().len(), 24);
+    assert_eq!(grid.get(0, 0, 0), Some((10_000_000 - 64_000 - 5) as f64));
+    assert_eq!(grid.get(2, 1, 3), Some((18_000_000 - 56_000 + 7) as f64));
+    assert_eq!(grid.values()[1], (14_000_000 - 64_000 - 5) as f64);
+    assert_eq!(grid.values()[3], (10_000_000 - 64_000 - 1) as f64);
+    assert_eq!(grid.layer(1).map(|layer| layer.len()), Some(12));
+    assert_eq!(grid.layer(1).map(|layer| layer[0]), grid.get(0, 1, 0));
+
+    assert_eq!(grid.get(3, 0, 0), None);
+    assert_eq!(grid.get(0, 2, 0), None);
+    assert_eq!(grid.layer(2), None);
+}
+
+#[test]
+fn grid_stats() {
+    let grid = NoiseGrid::from_fn(NoiseArea::flat(0, 0, 0, 4, 1), |x, _, _| x as f64 - 1.0);
+
+    assert_eq!(
+        grid.stats(),
+        Some(NoiseStats {
+            min: -1.0,
+            max: 2.0,
+            mean: 0.5
+        })
+    );
+    assert_eq!(
+        NoiseGrid::from_fn(NoiseArea::flat(0, 0, 0, 0, 5), |_, _, _| 0.0).stats(),
+        None
+    );
+}
+
+#[test]
+fn area_from_range() {
+    let range = Range {
+        scale: Scale::Chunk,
+        x: -2,
+        z: 3,
+        size_x: 5,
+        size_z: 6,
+        y: 15,
+        size_y: 0,
+    };
+
+    assert_eq!(
+        NoiseArea::from(range),
+        NoiseArea {
+            x: -32,
+            y: 60,
+            z: 48,
+            size_x: 5,
+            size_y: 1,
+            size_z: 6,
+            scale: 16,
+            scale_y: 4,
+        }
+    );
+
+    let range = Range {
+        scale: Scale::Block,
+        size_y: 3,
+        ..range
+    };
+    let area = NoiseArea::from(range);
+    assert_eq!((area.x, area.y, area.size_y, area.scale_y), (-2, 15, 3, 1));
+}
+
+#[test]
+#[cfg(feature = "image")]
+fn grid_normalization() {
+    use super::Normalization;
+
+    let grid = NoiseGrid::from_fn(NoiseArea::flat(0, 0, 0, 3, 1), |x, _, _| x as f64 * 2.0);
+
+    let img = grid
+        .to_gray_image(0, Normalization::MinMax)
+        .expect("layer 0 exists");
+    assert_eq!(img.into_raw(), vec![0, 128, 255]);
+
+    let img = grid
+        .to_gray_image(0, Normalization::Fixed { min: 1.0, max: 3.0 })
+        .expect("layer 0 exists");
+    assert_eq!(img.into_raw(), vec![0, 128, 255]);
+
+    let img = grid
+        .to_color_image(0, Normalization::MinMax, |value| {
+            [(value * 10.0) as u8, 0, 0].into()
+        })
+        .expect("layer 0 exists");
+    assert_eq!(img.into_raw(), vec![0, 0, 0, 5, 0, 0, 10, 0, 0]);
+
+    assert!(grid.to_gray_image(1, Normalization::MinMax).is_none());
+}
+
+#[test]
+#[cfg_attr(miri, ignore)]
+fn grids_match_single_samples() {
+    let area = NoiseArea {
+        x: -100,
+        y: 32,
+        z: 50,
+        size_x: 8,
+        size_y: 2,
+        size_z: 5,
+        scale: 4,
+        scale_y: 8,
+    };
+
+    let surface = SurfaceNoiseRelease::new(Dimension::DIM_OVERWORLD, SEED);
+    let grid = surface.sample_grid(area);
+    assert_eq!(grid.get(3, 1, 2), Some(surface.sample_raw(-88, 40, 58)));
+
+    let perlin = PerlinNoise::from_java_random(&mut JavaRandom::new(SEED));
+    let grid = perlin.sample_grid(area);
+    assert_eq!(grid.get(7, 0, 4), Some(perlin.sample(-72.0, 32.0, 66.0)));
+}
@@ -0,0 +1,100 @@
+//! Module for the seeds used when populating chunks
+//!
+//! After the terrain of a chunk is generated, minecraft populates it with
+//! features such as ores, trees and dungeons, and carves caves and ravines
+//! into it. Each of these steps seeds its own random number generator from the
+//! world seed and the position of the chunk. This module calculates those
+//! seeds, so they can be fed into your own feature placement or loot
+//! simulations.
+//!
+//! # Usage
+//! ```
+//! use cubiomes::enums::MCVersion;
+//! use cubiomes::population::{carver_seed, decorator_seed, population_seed};
+//!
+//! let version = MCVersion::MC_1_21_WD;
+//! let world_seed = -4804349823814383506;
+//!
+//! let population = population_seed(version, world_seed, 12, -7);
+//!
+//! // The seed for the third feature of the underground ores step
+//! let ores = decorator_seed(version, population, 2, 6);
+//!
+//! // The seed for the first carver
+//! let caves = carver_seed(version, world_seed, 12, -7, 0);
+//! ```
+//!
+//! # Details
+//!
+//! The population seed has changed over the versions. Before 1.13 it was
+//! derived from the chunk coordinates, since 1.13 it uses block coordinates of
+//! the chunk origin, and since 1.18 the multipliers come from the Xoroshiro
+//! generator instead of `java.util.Random`. All of this is handled by cubiomes.
+
+use crate::{enums::MCVersion, rng::JavaRandom};
+use cubiomes_sys::getPopulationSeed;
+
+/// Calculates the population seed of a chunk
+///
+/// This is the seed minecraft uses for the random generator when decorating
+/// the chunk at the given chunk coordinates.
+#[must_use]
+pub fn population_seed(version: MCVersion, world_seed: i64, chunk_x: i32, chunk_z: i32) -> i64 {
+    // Since 1.13 the seed is calculated from the block coordinates of the chunk
+    let (x, z) = if version >= MCVersion::MC_1_13_2 {
+        (chunk_x.wrapping_mul(16), chunk_z.wrapping_mul(16))
+    } else {
+        (chunk_x, chunk_z)
+    };
+
+    // SAFETY: The function only does arithmetic on its arguments
+    unsafe { getPopulationSeed(version as i32, world_seed as u64, x, z) as i64 }
+}
+
+/// Calculates the seed of a single decoration feature in a chunk
+///
+/// Since 1.13 every feature is placed with a random generator seeded from the
+/// population seed, the index of the feature within its generation step and
+/// the generation step itself.
+///
+/// Before 1.13 all features of a chunk share a single random generator seeded
+/// with the population seed, so the population seed is returned as is.
+#[must_use]
+pub fn decorator_seed(version: MCVersion, population_seed: i64, index: i32, step: i32) -> i64 {
+    if version >= MCVersion::MC_1_13_2 {
+        population_seed
+            .wrapping_add(index as i64)
+            .wrapping_add(10000 * step as i64)
+    } else {
+        population_seed
+    }
+}
+
+/// Calculates the seed of a carver in a chunk
+///
+/// Carvers (caves, ravines and canyons) are seeded from the world seed and the
+/// chunk coordinates. Since 1.13 each carver also offsets the world seed by its
+/// index, before 1.13 the index is ignored.
+///
+/// Unlike the population seed, carvers use `java.util.Random` in every
+/// version.
+#[must_use]
+pub fn carver_seed(
+    version: MCVersion,
+    world_seed: i64,
+    chunk_x: i32,
+    chunk_z: i32,
+    carver_index: i32,
+) -> i64 {
+    let seed = if version >= MCVersion::MC_1_13_2 {
+        world_seed.wrapping_add(carver_index as i64)
+    } else {
+        world_seed
+    };
+
+    let mut rng = JavaRandom::new(seed);
+    let a = rng.next_long();
+    let b = rng.next_long();
+
+    (chunk_x as i64).wrapping_mul(a) ^ (chunk_z as i64).wrapping_mul(b) ^ seed
+}
@@ -0,0 +1,235 @@
+//! Grid lines, borders and scale bars on maps
+
+use image::RgbImage;
+
+use super::{
+    fill_rect,
+    font::{draw_text, text_width, GLYPH_HEIGHT},
+    PixelMapping,
+};
+use crate::generator::Range;
+
+/// Width of a chunk in blocks
+const CHUNK_SIZE: u32 = 16;
+/// Width of a region file in blocks
+const REGION_SIZE: u32 = 512;
+/// Lines closer than this many pixels are left out, as they would cover the
+/// whole map
+const MIN_LINE_SPACING: f64 = 4.0;
+/// Distance in pixels from the edges of the map to the scale bar
+const MARGIN: i64 = 4;
+
+/// Coordinate grid lines
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct GridLines {
+    /// Distance between the lines in blocks
+    pub spacing: u32,
+    /// Color of the lines and labels
+    pub color: [u8; 3],
+    /// Whether the block coordinate of each line is drawn next to it
+    pub labels: bool,
+}
+
+/// A bar showing a distance on the map
+///
+/// The length of the bar is picked automatically as the longest 1, 2 or 5
+/// times a power of ten blocks which fits in `max_width`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct ScaleBar {
+    /// Maximum length of the bar in pixels
+    pub max_width: u32,
+    /// Color of the bar and its label
+    pub color: [u8; 3],
+    /// Color of a box behind the bar, [`None`] for no box
+    pub background: Option<[u8; 3]>,
+}
+
+impl Default for ScaleBar {
+    /// A black bar of at most 128 pixels on a white box
+    fn default() -> Self {
+        Self {
+            max_width: 128,
+            color: [0, 0, 0],
+            background: Some([255, 255, 255]),
+        }
+    }
+}
+
+/// Annotations drawn on a map
+///
+/// The default draws nothing, enable the annotations you need.
+///
+/// # Examples
+/// ```
+#[doc = include_str!("../../examples/render_legend.rs")]
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub struct Annotations {
+    /// Coordinate grid lines, [`None`] for no grid
+    pub grid: Option<GridLines>,
+    /// Color of the borders between chunks, [`None`] for no borders
+    pub chunk_borders: Option<[u8; 3]>,
+    /// Color of the borders between region files, which are 512 by 512
+    /// blocks, [`None`] for no borders
+    pub region_borders: Option<[u8; 3]>,
+    /// A scale bar in the bottom left corner, [`None`] for no scale bar
+    pub scale_bar: Option<ScaleBar>,
+}
+
+impl Annotations {
+    /// Draws the annotations on a map of the range
+    ///
+    /// The image should cover the range, like the output of
+    /// [`crate::generator::Cache::to_image()`]. Upscaled maps work as well.
+    ///
+    /// Chunk borders are drawn first, then region borders, the grid and last
+    /// the scale bar. Lines which would be less than 4 pixels apart are left
+    /// out, so chunk borders only show up on maps at 1:4 or finer.
+    pub fn draw(&self, img: &mut RgbImage, range: &Range) {
+        let pixels = PixelMapping::new(range, img);
+
+        if let Some(color) = self.chunk_borders {
+            draw_lines(img, &pixels, range, CHUNK_SIZE, color, false);
+        }
+
+        if let Some(color) = self.region_borders {
+            draw_lines(img, &pixels, range, REGION_SIZE, color, false);
+        }
+
+        if let Some(grid) = self.grid {
+            draw_lines(img, &pixels, range, grid.spacing, grid.color, grid.labels);
+        }
+
+        if let Some(scale_bar) = self.scale_bar {
+            draw_scale_bar(img, &pixels, scale_bar);
+        }
+    }
+}
+
+/// Gets the block coordinates between min and max which are multiples of the
+/// spacing
+fn multiples(min: i32, max: i32, spacing: u32) -> impl Iterator<Item = i32> {
+    let spacing = spacing as i64;
+    let first = (min as i64).div_euclid(spacing) * spacing;
+
+    (first..max as i64)
+        .step_by(spacing as usize)
+        .filter(move |coord| *coord >= min as i64)
+        .map(|coord| coord as i32)
+}
+
+/// Draws lines at every multiple of the spacing in blocks
+fn draw_lines(
+    img: &mut RgbImage,
+    pixels: &PixelMapping,
+    range: &Range,
+    spacing: u32,
+    color: [u8; 3],
+    labels: bool,
+) {
+    if spacing == 0 || (spacing as f64 * pixels.pixels_per_block()) < MIN_LINE_SPACING {
+        return;
+    }
+
+    let min = range.min_block_position();
+    let max = range.max_block_position();
+    let (width, height) = img.dimensions();
+
+    // Labels are left out where they would overlap the previous one
+    let mut label_end = i64::MIN;
+
+    for x in multiples(min.x, max.x, spacing) {
+        let column = pixels.x(x);
+        fill_rect(img, column, 0, 1, height, color);
+
+        let label = x.to_string();
+
+        if labels && column + 2 > label_end {
+            draw_text(img, column + 2, 2, &label, color, 1);
+            label_end = column + 2 + text_width(&label, 1) as i64 + 2;
+        }
+    }
+
+    label_end = i64::MIN;
+
+    for z in multiples(min.z, max.z, spacing) {
+        let row = pixels.z(z);
+        fill_rect(img, 0, row, width, 1, color);
+
+        if labels && row + 2 > label_end {
+            draw_text(img, 2, row + 2, &z.to_string(), color, 1);
+            label_end = row + 2 + GLYPH_HEIGHT as i64 + 2;
+        }
+    }
+}
+
+/// Gets the longest distance in blocks, and its length in pixels, which is 1,
+/// 2 or 5 times a power of ten and fits in the maximum width
+fn scale_bar_length(pixels_per_block: f64, max_width: u32) -> Option<(u32, u32)> {
+    (0..9)
+        .flat_map(|exponent| [1, 2, 5].map(|factor| factor * 10u32.pow(exponent)))
+        .map(|blocks| (blocks, (blocks as f64 * pixels_per_block).round()))
+        .take_while(|(_, width)| *width <= max_width as f64)
+        .filter(|(_, width)| *width >= 1.0)
+        .last()
+        .map(|(blocks, width)| (blocks, width as u32))
+}
+
+/// Draws the scale bar with its label above it in the bottom left corner
+fn draw_scale_bar(img: &mut RgbImage, pixels: &PixelMapping, scale_bar: ScaleBar) {
+    const PADDING: i64 = 2;
+    const TICK_HEIGHT: u32 = 5;
+    const BAR_HEIGHT: u32 = 2;
+
+    let Some((blocks, bar_width)) =
+        scale_bar_length(pixels.pixels_per_block(), scale_bar.max_width)
+    else {
+        return;
+    };
+
+    let label = match blocks {
+        1 => "1 block".to_string(),
+        _ => format!("{blocks} blocks"),
+    };
+    let label_width = text_width(&label, 1);
+
+    let box_width = bar_width.max(label_width) + 2 * PADDING as u32;
+    let box_height = GLYPH_HEIGHT + 2 + TICK_HEIGHT + 2 * PADDING as u32;
+
+    let left = MARGIN;
+    let top = img.height() as i64 - MARGIN - box_height as i64;
+
+    if let Some(background) = scale_bar.background {
+        fill_rect(img, left, top, box_width, box_height, background);
+    }
+
+    draw_text(
+        img,
+        left + PADDING,
+        top + PADDING,
+        &label,
+        scale_bar.color,
+        1,
+    );
+
+    let tick_top = top + PADDING + GLYPH_HEIGHT as i64 + 2;
+    let bar_right = left + PADDING + bar_width as i64 - 1;
+
+    fill_rect(
+        img,
+        left + PADDING,
+        tick_top,
+        1,
+        TICK_HEIGHT,
+        scale_bar.color,
+    );
+    fill_rect(img, bar_right, tick_top, 1, TICK_HEIGHT, scale_bar.color);
+    fill_rect(
+        img,
+        left + PADDING,
+        tick_top + (TICK_HEIGHT - BAR_HEIGHT) as i64,
+        bar_width,
+        BAR_HEIGHT,
+        scale_bar.color,
+    );
+}
@@ -0,0 +1,154 @@
+//! Legends for biome maps
+
+use image::RgbImage;
+
+use super::{
+    fill_rect,
+    font::{draw_text, text_width, GLYPH_HEIGHT},
+};
+use crate::{
+    colors::BiomeColorMap,
+    enums::{BiomeID, MCVersion},
+    generator::{error::GeneratorError, Cache},
+};
+
+/// Options for [`legend_image()`]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct LegendOptions {
+    /// Width and height of the color swatches in pixels
+    pub swatch_size: u32,
+    /// Size of the pixels of the text, 1 draws 7 pixel tall text
+    pub text_scale: u32,
+    /// Space in pixels around the legend and between the rows
+    pub padding: u32,
+    /// Color of the background
+    pub background: [u8; 3],
+    /// Color of the text and the outlines of the swatches
+    pub text_color: [u8; 3],
+}
+
+impl Default for LegendOptions {
+    /// Black text on white with 14 pixel swatches
+    fn default() -> Self {
+        Self {
+            swatch_size: 14,
+            text_scale: 1,
+            padding: 4,
+            background: [255, 255, 255],
+            text_color: [0, 0, 0],
+        }
+    }
+}
+
+/// Renders a legend with a color swatch and the name of each biome
+///
+/// The biomes are listed from top to bottom in the given order, named like
+/// in the given version with [`BiomeID::to_mc_biome_str()`]. The names are
+/// drawn in upper case with a small built in font.
+///
+/// See [`Cache::to_legend_image()`] for a legend of the biomes on a map.
+#[must_use]
+pub fn legend_image(
+    biomes: &[BiomeID],
+    version: MCVersion,
+    color_map: BiomeColorMap,
+    options: &LegendOptions,
+) -> RgbImage {
+    let LegendOptions {
+        swatch_size,
+        text_scale,
+        padding,
+        ..
+    } = *options;
+
+    let names: Vec<&str> = biomes
+        .iter()
+        .map(|biome| biome.to_mc_biome_str(version))
+        .collect();
+
+    let text_height = GLYPH_HEIGHT * text_scale;
+    let row_height = swatch_size.max(text_height);
+    let names_width = names
+        .iter()
+        .map(|name| text_width(name, text_scale))
+        .max()
+        .unwrap_or_default();
+
+    let mut img = RgbImage::from_pixel(
+        3 * padding + swatch_size + names_width,
+        padding + biomes.len() as u32 * (row_height + padding),
+        options.background.into(),
+    );
+
+    for (idx, (biome, name)) in biomes.iter().zip(names).enumerate() {
+        let top = (padding + idx as u32 * (row_height + padding)) as i64;
+        let swatch_top = top + ((row_height - swatch_size) / 2) as i64;
+
+        // The outline keeps light colors visible on the background
+        if swatch_size > 2 {
+            fill_rect(
+                &mut img,
+                padding as i64,
+                swatch_top,
+                swatch_size,
+                swatch_size,
+                options.text_color,
+            );
+            fill_rect(
+                &mut img,
+                padding as i64 + 1,
+                swatch_top + 1,
+                swatch_size - 2,
+                swatch_size - 2,
+                color_map[*biome],
+            );
+        } else {
+            fill_rect(
+                &mut img,
+                padding as i64,
+                swatch_top,
+                swatch_size,
+                swatch_size,
+                color_map[*biome],
+            );
+        }
+
+        draw_text(
+            &mut img,
+            (2 * padding + swatch_size) as i64,
+            top + ((row_height - text_height) / 2) as i64,
+            name,
+            options.text_color,
+            text_scale,
+        );
+    }
+
+    img
+}
+
+impl Cache<'_> {
+    /// Renders a legend of the biomes in the cache
+    ///
+    /// This function requires crate feature image
+    ///
+    /// The biomes are listed from the most to the least common, see
+    /// [`Self::biome_counts()`] and [`legend_image()`].
+    ///
+    /// # Errors
+    /// Returns an error if the cache contains a value which is not a valid
+    /// biome, or the generator has an invalid version.
+    pub fn to_legend_image(
+        &self,
+        color_map: BiomeColorMap,
+        options: &LegendOptions,
+    ) -> Result<RgbImage, GeneratorError> {
+        let version = self.generator().minecraft_version()?;
+        let biomes: Vec<BiomeID> = self
+            .biome_counts()?
+            .into_iter()
+            .map(|(biome, _)| biome)
+            .collect();
+
+        Ok(legend_image(&biomes, version, color_map, options))
+    }
+}
@@ -0,0 +1,124 @@
+//! Rendering maps from generated biomes and heights
+//!
+//! This module requires crate feature image
+//!
+//! The plain biome maps of [`crate::generator::Cache::to_image()`] don't show
+//! any terrain. [`relief_image()`] combines the biomes with approximate
+//! surface heights into a hillshaded map, which is a lot easier to read.
+//!
+//! Structures, strongholds and the spawn point can be marked on any map with
+//! a [`MapOverlay`], and grid lines, borders and a scale bar drawn with
+//! [`Annotations`]. [`legend_image()`] renders a legend explaining the biome
+//! colors.
+//!
+//! # Usage
+//! ```
+#![doc = include_str!("../../examples/render_relief.rs")]
+//! ```
+
+use thiserror::Error;
+
+use crate::generator::{error::GeneratorError, Range, Scale};
+
+mod annotations;
+mod font;
+mod legend;
+mod overlay;
+mod relief;
+pub use annotations::*;
+pub use legend::*;
+pub use overlay::*;
+pub use relief::*;
+
+#[cfg(test)]
+mod tests;
+
+/// An error rendering a map
+#[derive(Error, Debug, PartialEq, Eq, Hash, Clone, Copy)]
+pub enum RenderError {
+    /// The input is at a scale the renderer doesn't support
+    #[error("expected a range at scale 1:{} but got 1:{}", *.expected as i32, *.actual as i32)]
+    WrongScale {
+        /// The scale the renderer needs
+        expected: Scale,
+        /// The scale of the input
+        actual: Scale,
+    },
+    /// The biomes and heights don't cover the same area
+    #[error("the biomes and heights cover different areas")]
+    AreaMismatch,
+    /// Generating the biomes or heights failed
+    #[error(transparent)]
+    Generator(GeneratorError),
+}
+
+impl From<GeneratorError> for RenderError {
+    fn from(value: GeneratorError) -> Self {
+        Self::Generator(value)
+    }
+}
+
+/// Sets the pixel if it is inside the image
+fn put_pixel_clipped(img: &mut image::RgbImage, x: i64, y: i64, color: [u8; 3]) {
+    let (Ok(x), Ok(y)) = (u32::try_from(x), u32::try_from(y)) else {
+        return;
+    };
+
+    if x < img.width() && y < img.height() {
+        img.put_pixel(x, y, color.into());
+    }
+}
+
+/// Fills a rectangle with its top left corner at the pixel, clipping it to the
+/// image
+fn fill_rect(img: &mut image::RgbImage, x: i64, y: i64, width: u32, height: u32, color: [u8; 3]) {
+    for dy in 0..height as i64 {
+        for dx in 0..width as i64 {
+            put_pixel_clipped(img, x + dx, y + dy, color);
+        }
+    }
+}
+
+/// Converts block coordinates to pixels of an image covering a range
+///
+/// The image doesn't need to have one pixel per position of the range, so
+/// upscaled maps work as well.
+struct PixelMapping {
+    min_x: i64,
+    min_z: i64,
+    span_x: i64,
+    span_z: i64,
+    width: i64,
+    height: i64,
+}
+
+impl PixelMapping {
+    fn new(range: &Range, img: &image::RgbImage) -> Self {
+        let min = range.min_block_position();
+        let max = range.max_block_position();
+
+        Self {
+            min_x: min.x as i64,
+            min_z: min.z as i64,
+            span_x: (max.x as i64 - min.x as i64).max(1),
+            span_z: (max.z as i64 - min.z as i64).max(1),
+            width: img.width() as i64,
+            height: img.height() as i64,
+        }
+    }
+
+    /// Gets the pixel column containing the block x coordinate
+    fn x(&self, block_x: i32) -> i64 {
+        ((block_x as i64 - self.min_x) * self.width).div_euclid(self.span_x)
+    }
+
+    /// Gets the pixel row containing the block z coordinate
+    fn z(&self, block_z: i32) -> i64 {
+        ((block_z as i64 - self.min_z) * self.height).div_euclid(self.span_z)
+    }
+
+    /// Gets how many pixels wide a block is
+    fn pixels_per_block(&self) -> f64 {
+        self.width as f64 / self.span_x as f64
+    }
+}
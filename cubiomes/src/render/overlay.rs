@@ -0,0 +1,280 @@
+//! Structure, stronghold and spawn markers on biome maps
+
+use image::RgbImage;
+
+use super::{put_pixel_clipped, PixelMapping};
+use crate::{
+    enums::StructureType,
+    generator::{error::GeneratorError, BlockPosition, Generator, Range},
+    structures::StructureGenerationError,
+};
+
+/// What a [`Marker`] marks
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum MarkerKind {
+    /// A structure of the given type
+    Structure(StructureType),
+    /// A stronghold
+    Stronghold,
+    /// The world spawn point
+    Spawn,
+}
+
+/// A point of interest drawn on a map
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Marker {
+    /// Block position of the marker
+    pub position: BlockPosition,
+    /// What the marker marks
+    pub kind: MarkerKind,
+}
+
+/// The shape of a marker
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Glyph {
+    /// A filled square
+    Square,
+    /// A filled circle
+    Circle,
+    /// A square standing on its corner
+    Diamond,
+    /// A triangle pointing up
+    Triangle,
+    /// A plus sign
+    Cross,
+}
+
+/// How a marker is drawn
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct MarkerStyle {
+    /// The shape of the marker
+    pub glyph: Glyph,
+    /// The fill color of the marker
+    pub color: [u8; 3],
+    /// Color of a one pixel outline around the marker, [`None`] for no
+    /// outline
+    pub outline: Option<[u8; 3]>,
+    /// Distance in pixels from the center of the marker to its edge
+    pub radius: u32,
+}
+
+impl MarkerKind {
+    /// Gets the default style for markers of this kind
+    ///
+    /// Every structure type has its own combination of shape and color.
+    /// Strongholds and the spawn are drawn larger than structures so they
+    /// stand out.
+    #[must_use]
+    pub fn style(self) -> MarkerStyle {
+        let (glyph, color) = match self {
+            Self::Spawn => (Glyph::Cross, [255, 32, 32]),
+            Self::Stronghold => (Glyph::Diamond, [32, 160, 120]),
+            Self::Structure(structure_type) => structure_glyph(structure_type),
+        };
+
+        let radius = match self {
+            Self::Spawn | Self::Stronghold => 5,
+            Self::Structure(StructureType::Mansion | StructureType::Monument) => 4,
+            Self::Structure(_) => 3,
+        };
+
+        MarkerStyle {
+            glyph,
+            color,
+            outline: Some([0, 0, 0]),
+            radius,
+        }
+    }
+}
+
+fn structure_glyph(structure_type: StructureType) -> (Glyph, [u8; 3]) {
+    use StructureType as S;
+
+    match structure_type {
+        S::Desert_Pyramid => (Glyph::Triangle, [230, 200, 110]),
+        S::Jungle_Temple => (Glyph::Triangle, [40, 140, 40]),
+        S::Swamp_Hut => (Glyph::Square, [140, 60, 160]),
+        S::Igloo => (Glyph::Circle, [240, 240, 255]),
+        S::Village => (Glyph::Square, [170, 110, 50]),
+        S::Ocean_Ruin => (Glyph::Diamond, [60, 170, 170]),
+        S::Shipwreck => (Glyph::Diamond, [120, 80, 40]),
+        S::Monument => (Glyph::Square, [0, 200, 200]),
+        S::Mansion => (Glyph::Square, [90, 60, 30]),
+        S::Outpost => (Glyph::Triangle, [130, 130, 130]),
+        S::Ruined_Portal | S::Ruined_Portal_N => (Glyph::Diamond, [110, 40, 170]),
+        S::Ancient_City => (Glyph::Square, [30, 60, 80]),
+        S::Treasure => (Glyph::Cross, [255, 200, 0]),
+        S::Mineshaft => (Glyph::Cross, [150, 110, 70]),
+        S::Desert_Well => (Glyph::Circle, [80, 140, 230]),
+        S::Geode => (Glyph::Circle, [170, 90, 220]),
+        S::Fortress => (Glyph::Square, [150, 20, 20]),
+        S::Bastion => (Glyph::Square, [50, 50, 50]),
+        S::End_City => (Glyph::Diamond, [220, 210, 150]),
+        S::End_Gateway => (Glyph::Circle, [20, 20, 40]),
+        S::Trail_Ruins => (Glyph::Triangle, [180, 120, 80]),
+        S::Trial_Chambers => (Glyph::Square, [200, 120, 60]),
+        _ => (Glyph::Circle, [200, 200, 200]),
+    }
+}
+
+/// Markers to draw on a map of a [`Range`]
+///
+/// The markers are given in block positions, and converted to pixels through
+/// the range when drawn. Use the range of the [`crate::generator::Cache`] the
+/// map was made from, eg. `MapOverlay::new(*cache.range())` for the output
+/// of [`crate::generator::Cache::to_image()`].
+///
+/// # Examples
+/// ```
+#[doc = include_str!("../../examples/render_overlay.rs")]
+/// ```
+#[derive(Debug, Clone, PartialEq)]
+pub struct MapOverlay {
+    range: Range,
+    markers: Vec<Marker>,
+}
+
+impl MapOverlay {
+    /// Creates an overlay without markers for a map of the range
+    #[must_use]
+    pub fn new(range: Range) -> Self {
+        Self {
+            range,
+            markers: Vec::new(),
+        }
+    }
+
+    /// Gets the range of the map
+    #[must_use]
+    pub fn range(&self) -> &Range {
+        &self.range
+    }
+
+    /// Gets the markers of the overlay
+    #[must_use]
+    pub fn markers(&self) -> &[Marker] {
+        &self.markers
+    }
+
+    /// Adds a marker to the overlay
+    ///
+    /// Markers outside the range are kept, but only the part of them
+    /// reaching into the map is drawn.
+    pub fn push(&mut self, marker: Marker) {
+        self.markers.push(marker);
+    }
+
+    /// Adds markers for all structures of the type inside the range
+    ///
+    /// See [`Generator::structures_in_range()`].
+    ///
+    /// # Errors
+    /// Returns the errors of [`Generator::structures_in_range()`]
+    pub fn add_structures(
+        &mut self,
+        generator: &mut Generator,
+        structure_type: StructureType,
+    ) -> Result<(), StructureGenerationError> {
+        let positions = generator.structures_in_range(structure_type, self.range)?;
+
+        self.markers
+            .extend(positions.into_iter().map(|position| Marker {
+                position,
+                kind: MarkerKind::Structure(structure_type),
+            }));
+
+        Ok(())
+    }
+
+    /// Adds markers for all strongholds inside the range
+    pub fn add_strongholds(&mut self, generator: &Generator) {
+        let range = self.range;
+
+        self.markers.extend(
+            generator
+                .strongholds()
+                .filter(|position| range.is_inside(position.x, position.z))
+                .map(|position| Marker {
+                    position,
+                    kind: MarkerKind::Stronghold,
+                }),
+        );
+    }
+
+    /// Adds a marker for the spawn point if it is inside the range
+    ///
+    /// # Errors
+    /// Returns the errors of [`Generator::spawn()`]
+    pub fn add_spawn(&mut self, generator: &Generator) -> Result<(), GeneratorError> {
+        let position = generator.spawn()?;
+
+        if self.range.is_inside(position.x, position.z) {
+            self.markers.push(Marker {
+                position,
+                kind: MarkerKind::Spawn,
+            });
+        }
+
+        Ok(())
+    }
+
+    /// Draws the markers on the map with their default styles
+    ///
+    /// See [`Self::draw_with()`].
+    pub fn draw(&self, img: &mut RgbImage) {
+        self.draw_with(img, MarkerKind::style);
+    }
+
+    /// Draws the markers on the map with the style returned by `style`
+    ///
+    /// The image should cover the range of the overlay. It doesn't have to be
+    /// one pixel per position of the range, upscaled maps work as well. The
+    /// markers are drawn in the order they were added, so later markers are
+    /// drawn on top.
+    pub fn draw_with(&self, img: &mut RgbImage, style: impl Fn(MarkerKind) -> MarkerStyle) {
+        let pixels = PixelMapping::new(&self.range, img);
+
+        for marker in &self.markers {
+            draw_marker(
+                img,
+                pixels.x(marker.position.x),
+                pixels.z(marker.position.z),
+                style(marker.kind),
+            );
+        }
+    }
+}
+
+/// Draws a marker centered on the pixel, clipping it to the image
+fn draw_marker(img: &mut RgbImage, x: i64, z: i64, style: MarkerStyle) {
+    let radius = style.radius as i64;
+
+    if let Some(outline) = style.outline {
+        draw_glyph(img, x, z, style.glyph, radius + 1, outline);
+    }
+
+    draw_glyph(img, x, z, style.glyph, radius, style.color);
+}
+
+fn draw_glyph(img: &mut RgbImage, x: i64, z: i64, glyph: Glyph, radius: i64, color: [u8; 3]) {
+    for dz in -radius..=radius {
+        for dx in -radius..=radius {
+            if !glyph_contains(glyph, dx, dz, radius) {
+                continue;
+            }
+
+            put_pixel_clipped(img, x + dx, z + dz, color);
+        }
+    }
+}
+
+/// Checks if the offset from the center is part of the glyph
+fn glyph_contains(glyph: Glyph, dx: i64, dz: i64, radius: i64) -> bool {
+    match glyph {
+        Glyph::Square => true,
+        Glyph::Circle => dx * dx + dz * dz <= radius * radius + radius,
+        Glyph::Diamond => dx.abs() + dz.abs() <= radius,
+        Glyph::Triangle => 2 * dx.abs() <= dz + radius,
+        Glyph::Cross => dx.abs() <= radius / 3 || dz.abs() <= radius / 3,
+    }
+}
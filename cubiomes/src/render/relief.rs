@@ -0,0 +1,206 @@
+//! Hillshaded biome maps
+
+use image::RgbImage;
+
+use super::RenderError;
+use crate::{
+    colors::BiomeColorMap,
+    generator::{Cache, Heightmap},
+    noise::BiomeNoise,
+};
+
+/// Options for [`relief_image()`]
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct ReliefOptions {
+    /// Compass direction the sun shines from in degrees, clockwise from north
+    pub sun_azimuth: f32,
+    /// Angle of the sun above the horizon in degrees, should be above 0
+    pub sun_altitude: f32,
+    /// Multiplier for the heights when shading, which makes slopes steeper
+    pub exaggeration: f32,
+    /// How much the shading darkens or lightens the biome colors, 0 disables
+    /// shading
+    pub shade_strength: f32,
+    /// Height of the water surface, terrain below it is drawn as water
+    ///
+    /// Use [`None`] to draw all terrain with the biome colors.
+    pub water_level: Option<f32>,
+    /// Color of shallow water, deeper water is drawn darker
+    pub water_color: [u8; 3],
+}
+
+impl Default for ReliefOptions {
+    /// Sun from the north west at 45 degrees, and water at sea level
+    fn default() -> Self {
+        Self {
+            sun_azimuth: 315.0,
+            sun_altitude: 45.0,
+            exaggeration: 2.0,
+            shade_strength: 1.0,
+            water_level: Some(63.0),
+            water_color: [48, 96, 200],
+        }
+    }
+}
+
+/// How much darker the deepest water is than shallow water
+const MAX_WATER_DARKENING: f32 = 0.6;
+/// Depth in blocks at which water is drawn the darkest
+const MAX_WATER_DEPTH: f32 = 48.0;
+
+/// Renders a hillshaded map of the biomes in the cache
+///
+/// The cache must be a 2D cache at [`crate::generator::Scale::Quad`], and the heightmap must
+/// cover the same area, see [`crate::generator::Generator::heightmap()`].
+/// [`Cache::to_relief_image()`] generates the heights automatically.
+///
+/// Each pixel is colored by its biome and shaded by how much the terrain
+/// faces the sun. Terrain below the water level is drawn as water, getting
+/// darker the deeper it is.
+///
+/// # Errors
+/// Returns [`RenderError::WrongScale`] if the cache is not at
+/// [`crate::generator::Scale::Quad`], [`RenderError::AreaMismatch`] if the cache is 3D or the
+/// heightmap covers a different area, and [`RenderError::Generator`] if the
+/// cache contains an invalid biome.
+pub fn relief_image(
+    cache: &Cache,
+    heightmap: &Heightmap,
+    color_map: BiomeColorMap,
+    options: &ReliefOptions,
+) -> Result<RgbImage, RenderError> {
+    let range = cache.range();
+
+    if range.scale != Heightmap::SCALE {
+        return Err(RenderError::WrongScale {
+            expected: Heightmap::SCALE,
+            actual: range.scale,
+        });
+    }
+
+    if range.size_y > 1
+        || (range.x, range.z, range.size_x, range.size_z)
+            != (
+                heightmap.x(),
+                heightmap.z(),
+                heightmap.size_x(),
+                heightmap.size_z(),
+            )
+    {
+        return Err(RenderError::AreaMismatch);
+    }
+
+    let sun = sun_direction(options.sun_azimuth, options.sun_altitude);
+    // Flat terrain keeps its biome color
+    let flat_light = sun[1].max(f32::EPSILON);
+
+    let mut img = RgbImage::new(range.size_x, range.size_z);
+
+    for z in 0..range.size_z {
+        for x in 0..range.size_x {
+            let height = height_clamped(heightmap, x as i64, z as i64);
+
+            let color = match options.water_level {
+                Some(water_level) if height < water_level => {
+                    let depth = ((water_level - height) / MAX_WATER_DEPTH).min(1.0);
+                    shade(options.water_color, 1.0 - depth * MAX_WATER_DARKENING)
+                }
+                _ => {
+                    let light = light(heightmap, x, z, sun, options.exaggeration);
+                    let factor = 1.0 + options.shade_strength * (light / flat_light - 1.0);
+
+                    shade(color_map[cache.biome_at(x, 0, z)?], factor)
+                }
+            };
+
+            img.put_pixel(x, z, color.into());
+        }
+    }
+
+    Ok(img)
+}
+
+impl Cache<'_> {
+    /// Renders a hillshaded map of the biomes in the cache
+    ///
+    /// This function requires crate feature image
+    ///
+    /// Generates the heights for the cache with the generator of the cache and
+    /// then renders the map with [`relief_image()`]. See
+    /// [`crate::generator::Generator::approx_surface_noise()`] for which
+    /// surface noise to use.
+    ///
+    /// # Errors
+    /// Returns the errors of [`relief_image()`], and
+    /// [`RenderError::Generator`] if the heights can't be generated.
+    pub fn to_relief_image(
+        &self,
+        surface_noise: &BiomeNoise,
+        color_map: BiomeColorMap,
+        options: &ReliefOptions,
+    ) -> Result<RgbImage, RenderError> {
+        let range = self.range();
+
+        if range.scale != Heightmap::SCALE {
+            return Err(RenderError::WrongScale {
+                expected: Heightmap::SCALE,
+                actual: range.scale,
+            });
+        }
+
+        let heightmap = self.generator().heightmap(
+            range.x,
+            range.z,
+            range.size_x,
+            range.size_z,
+            surface_noise,
+        )?;
+
+        relief_image(self, &heightmap, color_map, options)
+    }
+}
+
+/// Gets the unit vector pointing towards the sun, with x to the east, y up
+/// and z to the south
+fn sun_direction(azimuth: f32, altitude: f32) -> [f32; 3] {
+    let (azimuth, altitude) = (azimuth.to_radians(), altitude.to_radians());
+
+    [
+        azimuth.sin() * altitude.cos(),
+        altitude.sin(),
+        -azimuth.cos() * altitude.cos(),
+    ]
+}
+
+/// Gets how much light the terrain at a position gets from the sun
+fn light(heightmap: &Heightmap, x: u32, z: u32, sun: [f32; 3], exaggeration: f32) -> f32 {
+    let (x, z) = (x as i64, z as i64);
+    let distance = 2.0 * Heightmap::SCALE as i32 as f32;
+
+    let slope_x = (height_clamped(heightmap, x + 1, z) - height_clamped(heightmap, x - 1, z))
+        * exaggeration
+        / distance;
+    let slope_z = (height_clamped(heightmap, x, z + 1) - height_clamped(heightmap, x, z - 1))
+        * exaggeration
+        / distance;
+
+    let normal = [-slope_x, 1.0, -slope_z];
+    let length = (normal[0] * normal[0] + 1.0 + normal[2] * normal[2]).sqrt();
+
+    let light = (normal[0] * sun[0] + normal[1] * sun[1] + normal[2] * sun[2]) / length;
+
+    light.max(0.0)
+}
+
+/// Gets the height at a position, using the nearest edge for positions
+/// outside the heightmap
+fn height_clamped(heightmap: &Heightmap, x: i64, z: i64) -> f32 {
+    let x = x.clamp(0, heightmap.size_x() as i64 - 1) as u32;
+    let z = z.clamp(0, heightmap.size_z() as i64 - 1) as u32;
+
+    heightmap.get(x, z).unwrap_or_default()
+}
+
+fn shade(color: [u8; 3], factor: f32) -> [u8; 3] {
+    color.map(|channel| (channel as f32 * factor).round().clamp(0.0, 255.0) as u8)
+}
@@ -0,0 +1,485 @@
+use super::font::{draw_text, text_width};
+use super::{
+    legend_image, relief_image, Annotations, Glyph, GridLines, LegendOptions, MapOverlay, Marker,
+    MarkerKind, MarkerStyle, ReliefOptions, RenderError, ScaleBar,
+};
+use crate::colors::BiomeColorMap;
+use crate::enums::{BiomeID, Dimension, MCVersion, StructureType};
+use crate::generator::{BlockPosition, Cache, Generator, GeneratorFlags, Heightmap, Range, Scale};
+use crate::noise::BiomeNoise;
+use cubiomes_sys::num_traits::FromPrimitive;
+
+const SEED: i64 = -4804349703814383506;
+const RANGE: Range = Range {
+    scale: Scale::Quad,
+    x: -40,
+    z: 12,
+    size_x: 24,
+    size_z: 16,
+    y: 16,
+    size_y: 0,
+};
+
+fn init_generator() -> Generator {
+    Generator::new(
+        MCVersion::MC_1_21_WD,
+        SEED,
+        Dimension::DIM_OVERWORLD,
+        GeneratorFlags::empty(),
+    )
+}
+
+fn heightmap_from_fn(range: Range, height: impl Fn(u32, u32) -> f32) -> Heightmap {
+    let heights = (0..range.size_z)
+        .flat_map(|z| (0..range.size_x).map(move |x| (x, z)))
+        .map(|(x, z)| height(x, z))
+        .collect();
+
+    Heightmap::new(range.x, range.z, range.size_x, range.size_z, heights)
+        .expect("the heights cover the range")
+}
+
+fn brightness(pixel: &image::Rgb<u8>) -> u32 {
+    pixel.0.iter().map(|channel| *channel as u32).sum()
+}
+
+#[test]
+#[cfg_attr(miri, ignore)]
+fn flat_relief_is_biome_map() {
+    let generator = init_generator();
+    let cache = Cache::new(&generator, RANGE).expect("1:4 is supported");
+    let heightmap = heightmap_from_fn(RANGE, |_, _| 100.0);
+
+    let relief = relief_image(
+        &cache,
+        &heightmap,
+        BiomeColorMap::new(),
+        &ReliefOptions::default(),
+    )
+    .expect("the heightmap matches the cache");
+
+    assert_eq!(
+        relief,
+        cache
+            .to_image(BiomeColorMap::new())
+            .expect("the biomes are valid")
+    );
+}
+
+#[test]
+#[cfg_attr(miri, ignore)]
+fn slopes_facing_the_sun_are_lighter() {
+    let generator = init_generator();
+    let cache = Cache::new(&generator, RANGE).expect("1:4 is supported");
+    // Rises towards the east
+    let heightmap = heightmap_from_fn(RANGE, |x, _| 70.0 + x as f32 * 4.0);
+    let flat = cache
+        .to_image(BiomeColorMap::new())
+        .expect("the biomes are valid");
+
+    let from_west = ReliefOptions {
+        sun_azimuth: 270.0,
+        ..Default::default()
+    };
+    let from_east = ReliefOptions {
+        sun_azimuth: 90.0,
+        ..Default::default()
+    };
+
+    let lit = relief_image(&cache, &heightmap, BiomeColorMap::new(), &from_west)
+        .expect("the heightmap matches the cache");
+    let shadowed = relief_image(&cache, &heightmap, BiomeColorMap::new(), &from_east)
+        .expect("the heightmap matches the cache");
+
+    for ((lit, shadowed), flat) in lit.pixels().zip(shadowed.pixels()).zip(flat.pixels()) {
+        assert!(brightness(lit) >= brightness(flat));
+        assert!(brightness(shadowed) < brightness(flat));
+    }
+}
+
+#[test]
+#[cfg_attr(miri, ignore)]
+fn terrain_below_water_level_is_water() {
+    let generator = init_generator();
+    let cache = Cache::new(&generator, RANGE).expect("1:4 is supported");
+    // The west half is shallow and the east half deep water
+    let heightmap = heightmap_from_fn(RANGE, |x, _| if x < 12 { 62.0 } else { 0.0 });
+
+    let options = ReliefOptions {
+        water_level: Some(63.0),
+        water_color: [100, 100, 200],
+        ..Default::default()
+    };
+    let relief = relief_image(&cache, &heightmap, BiomeColorMap::new(), &options)
+        .expect("the heightmap matches the cache");
+
+    assert_eq!(relief.get_pixel(0, 0).0, [99, 99, 198]);
+    assert_eq!(relief.get_pixel(23, 15).0, [40, 40, 80]);
+
+    let options = ReliefOptions {
+        water_level: None,
+        ..options
+    };
+    let relief = relief_image(&cache, &heightmap, BiomeColorMap::new(), &options)
+        .expect("the heightmap matches the cache");
+    let flat = cache
+        .to_image(BiomeColorMap::new())
+        .expect("the biomes are valid");
+
+    assert_eq!(relief.get_pixel(0, 0), flat.get_pixel(0, 0));
+}
+
+#[test]
+#[cfg_attr(miri, ignore)]
+fn relief_needs_matching_quad_inputs() {
+    let generator = init_generator();
+    let noise = BiomeNoise::new(MCVersion::MC_1_21_WD, Dimension::DIM_OVERWORLD, SEED);
+    let options = ReliefOptions::default();
+
+    let chunk_range = Range {
+        scale: Scale::Chunk,
+        ..RANGE
+    };
+    let cache = Cache::new(&generator, chunk_range).expect("1:16 is supported");
+    assert_eq!(
+        cache.to_relief_image(&noise, BiomeColorMap::new(), &options),
+        Err(RenderError::WrongScale {
+            expected: Scale::Quad,
+            actual: Scale::Chunk
+        })
+    );
+
+    let cache = Cache::new(&generator, RANGE).expect("1:4 is supported");
+    let shifted = heightmap_from_fn(Range { x: -39, ..RANGE }, |_, _| 64.0);
+    assert_eq!(
+        relief_image(&cache, &shifted, BiomeColorMap::new(), &options),
+        Err(RenderError::AreaMismatch)
+    );
+
+    let heightmap = generator
+        .heightmap(RANGE.x, RANGE.z, RANGE.size_x, RANGE.size_z, &noise)
+        .expect("the overworld has heights");
+    assert_eq!(
+        cache.to_relief_image(&noise, BiomeColorMap::new(), &options),
+        relief_image(&cache, &heightmap, BiomeColorMap::new(), &options)
+    );
+}
+
+const WHITE: [u8; 3] = [255, 255, 255];
+
+fn changed_pixels(img: &image::RgbImage) -> Vec<(u32, u32)> {
+    img.enumerate_pixels()
+        .filter(|(_, _, pixel)| pixel.0 != WHITE)
+        .map(|(x, z, _)| (x, z))
+        .collect()
+}
+
+#[test]
+fn markers_are_placed_through_range() {
+    let mut overlay = MapOverlay::new(RANGE);
+    overlay.push(Marker {
+        position: BlockPosition::new(-139, 60),
+        kind: MarkerKind::Spawn,
+    });
+    // Far outside the map
+    overlay.push(Marker {
+        position: BlockPosition::new(-1000, 0),
+        kind: MarkerKind::Stronghold,
+    });
+
+    let dot = |_| MarkerStyle {
+        glyph: Glyph::Square,
+        color: [255, 0, 0],
+        outline: None,
+        radius: 0,
+    };
+
+    let mut img = image::RgbImage::from_pixel(RANGE.size_x, RANGE.size_z, WHITE.into());
+    overlay.draw_with(&mut img, dot);
+    assert_eq!(changed_pixels(&img), vec![(5, 3)]);
+
+    // Upscaled maps have the markers at the same spot
+    let mut img = image::RgbImage::from_pixel(RANGE.size_x * 2, RANGE.size_z * 2, WHITE.into());
+    overlay.draw_with(&mut img, dot);
+    assert_eq!(changed_pixels(&img), vec![(10, 6)]);
+}
+
+#[test]
+fn glyph_shapes() {
+    let mut overlay = MapOverlay::new(RANGE);
+    overlay.push(Marker {
+        position: BlockPosition::new(-112, 80),
+        kind: MarkerKind::Spawn,
+    });
+
+    let count = |glyph, outline| {
+        let mut img = image::RgbImage::from_pixel(RANGE.size_x, RANGE.size_z, WHITE.into());
+        overlay.draw_with(&mut img, |_| MarkerStyle {
+            glyph,
+            color: [255, 0, 0],
+            outline,
+            radius: 2,
+        });
+        changed_pixels(&img).len()
+    };
+
+    assert_eq!(count(Glyph::Square, None), 25);
+    assert_eq!(count(Glyph::Circle, None), 21);
+    assert_eq!(count(Glyph::Diamond, None), 13);
+    assert_eq!(count(Glyph::Triangle, None), 13);
+    assert_eq!(count(Glyph::Cross, None), 9);
+    assert_eq!(count(Glyph::Square, Some([0, 0, 0])), 49);
+
+    // Markers on the edge are clipped instead of panicking
+    let mut overlay = MapOverlay::new(RANGE);
+    overlay.push(Marker {
+        position: BlockPosition::new(-160, 48),
+        kind: MarkerKind::Spawn,
+    });
+    let mut img = image::RgbImage::from_pixel(RANGE.size_x, RANGE.size_z, WHITE.into());
+    overlay.draw(&mut img);
+    assert!(!changed_pixels(&img).is_empty());
+}
+
+#[test]
+fn every_structure_has_a_style() {
+    let styles: Vec<(StructureType, MarkerStyle)> = (0..StructureType::FEATURE_NUM as i32)
+        .map(|id| StructureType::from_i32(id).expect("ids below FEATURE_NUM are structures"))
+        // The generic feature and end islands are not structures of their
+        // own, and nether portals look like overworld ones
+        .filter(|structure_type| {
+            !matches!(
+                structure_type,
+                StructureType::Feature | StructureType::End_Island | StructureType::Ruined_Portal_N
+            )
+        })
+        .map(|structure_type| {
+            (
+                structure_type,
+                MarkerKind::Structure(structure_type).style(),
+            )
+        })
+        .collect();
+
+    for (idx, (structure_type, style)) in styles.iter().enumerate() {
+        for (other_type, other_style) in &styles[idx + 1..] {
+            assert_ne!(style, other_style, "{structure_type:?} and {other_type:?}");
+        }
+    }
+
+    assert_eq!(
+        MarkerKind::Structure(StructureType::Ruined_Portal_N).style(),
+        MarkerKind::Structure(StructureType::Ruined_Portal).style()
+    );
+
+    assert!(
+        MarkerKind::Spawn.style().radius
+            > MarkerKind::Structure(StructureType::Village).style().radius
+    );
+}
+
+#[test]
+#[cfg_attr(miri, ignore)]
+fn overlay_markers_are_inside_range() {
+    let mut generator = init_generator();
+    let spawn = generator
+        .spawn()
+        .expect("the generator is in the overworld");
+
+    let range = Range {
+        scale: Scale::Chunk,
+        x: spawn.x.div_euclid(16) - 64,
+        z: spawn.z.div_euclid(16) - 64,
+        size_x: 128,
+        size_z: 128,
+        y: 16,
+        size_y: 0,
+    };
+
+    let mut img = Cache::new(&generator, range)
+        .expect("1:16 is supported")
+        .to_image(BiomeColorMap::new())
+        .expect("the biomes are valid");
+    let map = img.clone();
+
+    let mut overlay = MapOverlay::new(range);
+    overlay
+        .add_structures(&mut generator, StructureType::Village)
+        .expect("villages exist in 1.21");
+    overlay.add_strongholds(&generator);
+    overlay
+        .add_spawn(&generator)
+        .expect("the generator is in the overworld");
+
+    assert!(overlay
+        .markers()
+        .iter()
+        .all(|marker| range.is_inside(marker.position.x, marker.position.z)));
+    assert_eq!(
+        overlay
+            .markers()
+            .iter()
+            .filter(|marker| marker.kind == MarkerKind::Spawn)
+            .count(),
+        1
+    );
+
+    overlay.draw(&mut img);
+    assert_ne!(img, map);
+}
+
+#[test]
+fn text_is_clipped_and_measured() {
+    assert_eq!(text_width("", 1), 0);
+    assert_eq!(text_width("abc", 1), 17);
+    assert_eq!(text_width("abc", 2), 34);
+
+    // Lower case letters are drawn like upper case ones
+    let mut lower = image::RgbImage::from_pixel(20, 9, WHITE.into());
+    let mut upper = lower.clone();
+    draw_text(&mut lower, 1, 1, "ab", [0, 0, 0], 1);
+    draw_text(&mut upper, 1, 1, "AB", [0, 0, 0], 1);
+    assert_eq!(lower, upper);
+    assert!(!changed_pixels(&lower).is_empty());
+
+    // Partly outside the image
+    draw_text(&mut lower, -3, 5, "x~", [0, 0, 0], 3);
+}
+
+#[test]
+fn chunk_and_region_borders() {
+    let range = Range {
+        scale: Scale::Block,
+        x: -8,
+        z: 0,
+        size_x: 24,
+        size_z: 20,
+        y: 64,
+        size_y: 0,
+    };
+
+    let mut img = image::RgbImage::from_pixel(24, 20, WHITE.into());
+    Annotations {
+        chunk_borders: Some([255, 0, 0]),
+        ..Default::default()
+    }
+    .draw(&mut img, &range);
+
+    for (x, z, pixel) in img.enumerate_pixels() {
+        assert_eq!(pixel.0 != WHITE, x == 8 || z == 0 || z == 16, "{x}, {z}");
+    }
+
+    // At 1:16 every pixel is a chunk, so only the region borders are drawn
+    let range = Range {
+        scale: Scale::Chunk,
+        x: -40,
+        z: -8,
+        size_x: 48,
+        size_z: 16,
+        y: 16,
+        size_y: 0,
+    };
+
+    let mut img = image::RgbImage::from_pixel(48, 16, WHITE.into());
+    Annotations {
+        chunk_borders: Some([255, 0, 0]),
+        region_borders: Some([0, 0, 255]),
+        ..Default::default()
+    }
+    .draw(&mut img, &range);
+
+    for (x, z, pixel) in img.enumerate_pixels() {
+        let expected = if x == 8 || x == 40 || z == 8 {
+            [0, 0, 255]
+        } else {
+            WHITE
+        };
+        assert_eq!(pixel.0, expected, "{x}, {z}");
+    }
+}
+
+#[test]
+fn grid_lines_follow_upscaled_maps() {
+    let mut img = image::RgbImage::from_pixel(RANGE.size_x * 2, RANGE.size_z * 2, WHITE.into());
+    Annotations {
+        grid: Some(GridLines {
+            spacing: 32,
+            color: [0, 0, 0],
+            labels: false,
+        }),
+        ..Default::default()
+    }
+    .draw(&mut img, &RANGE);
+
+    // Blocks -160..-64 along x and 48..112 along z, at half a pixel per block
+    for (x, z, pixel) in img.enumerate_pixels() {
+        let on_line = x == 0 || x == 16 || x == 32 || z == 8 || z == 24;
+        assert_eq!(pixel.0 != WHITE, on_line, "{x}, {z}");
+    }
+}
+
+#[test]
+fn scale_bar_lengths() {
+    let bar = Annotations {
+        scale_bar: Some(ScaleBar {
+            max_width: 100,
+            color: [255, 0, 0],
+            background: None,
+        }),
+        ..Default::default()
+    };
+
+    let bar_width = |scale| {
+        let range = Range {
+            scale,
+            x: 0,
+            z: 0,
+            size_x: 200,
+            size_z: 40,
+            y: 16,
+            size_y: 0,
+        };
+        let mut img = image::RgbImage::from_pixel(200, 40, WHITE.into());
+        bar.draw(&mut img, &range);
+
+        // The lower row of the bar
+        (0..200)
+            .filter(|x| img.get_pixel(*x, 40 - 7).0 != WHITE)
+            .count()
+    };
+
+    // 100 blocks at 1:1 and 200 blocks at 1:4
+    assert_eq!(bar_width(Scale::Block), 100);
+    assert_eq!(bar_width(Scale::Quad), 50);
+    // 20000 blocks at 1:256
+    assert_eq!(bar_width(Scale::HalfRegion), 78);
+}
+
+#[test]
+#[cfg_attr(miri, ignore)]
+fn legend_has_a_row_per_biome() {
+    let color_map = BiomeColorMap::new();
+    let options = LegendOptions::default();
+
+    let legend = legend_image(
+        &[BiomeID::plains, BiomeID::desert],
+        MCVersion::MC_1_21_WD,
+        color_map,
+        &options,
+    );
+
+    // Padding, swatch, padding and the six letter names, with two rows
+    assert_eq!(legend.dimensions(), (12 + 14 + 35, 4 + 2 * (14 + 4)));
+    assert_eq!(legend.get_pixel(4, 4).0, options.text_color);
+    assert_eq!(legend.get_pixel(11, 11).0, color_map[BiomeID::plains]);
+    assert_eq!(legend.get_pixel(11, 29).0, color_map[BiomeID::desert]);
+
+    let generator = init_generator();
+    let cache = Cache::new(&generator, RANGE).expect("1:4 is supported");
+    let legend = cache
+        .to_legend_image(color_map, &options)
+        .expect("the biomes are valid");
+    let biomes = cache.biome_counts().expect("the biomes are valid").len() as u32;
+
+    assert_eq!(legend.height(), 4 + biomes * 18);
+}
@@ -0,0 +1,241 @@
+//! Random number generators used by minecraft world generation
+//!
+//! Minecraft uses the linear congruential generator of `java.util.Random`,
+//! here [`JavaRandom`], for most of its world generation. Since 1.18 some
+//! parts, like noise and the positional randomness of features, use
+//! [`Xoroshiro`] instead.
+//!
+//! Both generators produce the exact same numbers as in java and as the
+//! implementations in cubiomes, which makes them usable for simulating loot,
+//! slime chunks and other things cubiomes doesn't provide.
+//!
+//! # Usage
+//! ```
+//! use cubiomes::rng::JavaRandom;
+//!
+//! // A chunk is a slime chunk if this is zero
+//! fn is_slime_chunk(seed: i64, chunk_x: i32, chunk_z: i32) -> bool {
+//!     let mut rng = JavaRandom::new(
+//!         seed.wrapping_add((chunk_x.wrapping_mul(chunk_x).wrapping_mul(0x4c1906)) as i64)
+//!             .wrapping_add((chunk_x.wrapping_mul(0x5ac0db)) as i64)
+//!             .wrapping_add((chunk_z.wrapping_mul(chunk_z) as i64).wrapping_mul(0x4307a7))
+//!             .wrapping_add((chunk_z.wrapping_mul(0x5f24f)) as i64)
+//!             ^ 0x3ad8025f,
+//!     );
+//!
+//!     rng.next_int(10) == 0
+//! }
+//!
+//! println!("{}", is_slime_chunk(-4804349703814383506, 0, 0));
+//! ```
+
+const MULTIPLIER: u64 = 0x5deece66d;
+const ADDEND: u64 = 0xb;
+const MASK: u64 = (1 << 48) - 1;
+
+/// The linear congruential generator used by `java.util.Random`
+///
+/// Only the lower 48 bits of the seed affect the generated numbers.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct JavaRandom {
+    seed: u64,
+}
+
+impl JavaRandom {
+    /// Creates a new generator, like `new Random(seed)` in java
+    #[must_use]
+    pub fn new(seed: i64) -> Self {
+        Self {
+            seed: (seed as u64 ^ MULTIPLIER) & MASK,
+        }
+    }
+
+    /// Sets the seed of the generator, like `Random.setSeed(seed)` in java
+    pub fn set_seed(&mut self, seed: i64) {
+        *self = Self::new(seed);
+    }
+
+    /// Gets the internal state, which is what cubiomes uses as the seed
+    pub(crate) fn state_mut(&mut self) -> &mut u64 {
+        &mut self.seed
+    }
+
+    fn next_bits(&mut self, bits: u32) -> i32 {
+        self.seed = (self.seed.wrapping_mul(MULTIPLIER).wrapping_add(ADDEND)) & MASK;
+
+        ((self.seed as i64) >> (48 - bits)) as i32
+    }
+
+    /// Gets the next random integer in `0..bound`, like `Random.nextInt(bound)`
+    /// in java
+    ///
+    /// # Panics
+    /// Panics if bound is not positive
+    pub fn next_int(&mut self, bound: i32) -> i32 {
+        assert!(bound > 0, "bound must be positive");
+
+        if bound & (bound - 1) == 0 {
+            return ((bound as i64 * self.next_bits(31) as i64) >> 31) as i32;
+        }
+
+        loop {
+            let bits = self.next_bits(31);
+            let val = bits % bound;
+
+            if bits.wrapping_sub(val).wrapping_add(bound - 1) >= 0 {
+                return val;
+            }
+        }
+    }
+
+    /// Gets the next random long, like `Random.nextLong()` in java
+    pub fn next_long(&mut self) -> i64 {
+        ((self.next_bits(32) as i64) << 32).wrapping_add(self.next_bits(32) as i64)
+    }
+
+    /// Gets the next random float in `0.0..1.0`, like `Random.nextFloat()` in
+    /// java
+    pub fn next_float(&mut self) -> f32 {
+        self.next_bits(24) as f32 / (1 << 24) as f32
+    }
+
+    /// Gets the next random double in `0.0..1.0`, like `Random.nextDouble()`
+    /// in java
+    pub fn next_double(&mut self) -> f64 {
+        let upper = (self.next_bits(26) as i64) << 27;
+        let lower = self.next_bits(27) as i64;
+
+        (upper + lower) as f64 / (1u64 << 53) as f64
+    }
+
+    /// Advances the generator by `n` steps without generating the numbers
+    ///
+    /// [`Self::next_float()`] takes one step, [`Self::next_long()`] and
+    /// [`Self::next_double()`] take two, and [`Self::next_int()`] takes one
+    /// unless the bound isn't a power of two and a value gets rejected. Runs
+    /// in logarithmic time, so even skipping `u64::MAX` steps is fast.
+    pub fn skip(&mut self, n: u64) {
+        let mut multiplier = 1u64;
+        let mut addend = 0u64;
+
+        let mut step_multiplier = MULTIPLIER;
+        let mut step_addend = ADDEND;
+
+        let mut remaining = n;
+        while remaining != 0 {
+            if remaining & 1 == 1 {
+                multiplier = multiplier.wrapping_mul(step_multiplier);
+                addend = step_multiplier
+                    .wrapping_mul(addend)
+                    .wrapping_add(step_addend);
+            }
+
+            step_addend = step_multiplier.wrapping_add(1).wrapping_mul(step_addend);
+            step_multiplier = step_multiplier.wrapping_mul(step_multiplier);
+            remaining >>= 1;
+        }
+
+        self.seed = self.seed.wrapping_mul(multiplier).wrapping_add(addend) & MASK;
+    }
+}
+
+/// The Xoroshiro128++ generator used by minecraft since 1.18
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Xoroshiro {
+    lo: u64,
+    hi: u64,
+}
+
+impl Xoroshiro {
+    /// Creates a new generator from a seed, like `new XoroshiroRandomSource(seed)`
+    /// in minecraft
+    #[must_use]
+    pub fn new(seed: i64) -> Self {
+        const XL: u64 = 0x9e3779b97f4a7c15;
+        const XH: u64 = 0x6a09e667f3bcc909;
+
+        let lo = seed as u64 ^ XH;
+        let hi = lo.wrapping_add(XL);
+
+        Self {
+            lo: mix_stafford_13(lo),
+            hi: mix_stafford_13(hi),
+        }
+    }
+
+    /// Sets the seed of the generator, like `setSeed(seed)` in minecraft
+    pub fn set_seed(&mut self, seed: i64) {
+        *self = Self::new(seed);
+    }
+
+    /// Runs `f` with the state of the generator converted for cubiomes,
+    /// keeping any advancement of the state
+    pub(crate) fn with_raw<T>(&mut self, f: impl FnOnce(&mut cubiomes_sys::Xoroshiro) -> T) -> T {
+        let mut raw = cubiomes_sys::Xoroshiro {
+            lo: self.lo,
+            hi: self.hi,
+        };
+        let result = f(&mut raw);
+
+        self.lo = raw.lo;
+        self.hi = raw.hi;
+        result
+    }
+
+    /// Gets the next random long
+    pub fn next_long(&mut self) -> i64 {
+        let Self { lo, mut hi } = *self;
+        let n = lo.wrapping_add(hi).rotate_left(17).wrapping_add(lo);
+
+        hi ^= lo;
+        self.lo = lo.rotate_left(49) ^ hi ^ (hi << 21);
+        self.hi = hi.rotate_left(28);
+
+        n as i64
+    }
+
+    /// Gets the next random integer in `0..bound`
+    ///
+    /// # Panics
+    /// Panics if bound is not positive
+    pub fn next_int(&mut self, bound: i32) -> i32 {
+        assert!(bound > 0, "bound must be positive");
+        let bound = bound as u64;
+
+        let mut r = (self.next_long() as u64 & 0xffff_ffff) * bound;
+
+        if (r as u32 as u64) < bound {
+            let threshold = bound.wrapping_neg() as u32 as u64 % bound;
+
+            while (r as u32 as u64) < threshold {
+                r = (self.next_long() as u64 & 0xffff_ffff) * bound;
+            }
+        }
+
+        (r >> 32) as i32
+    }
+
+    /// Gets the next random float in `0.0..1.0`
+    pub fn next_float(&mut self) -> f32 {
+        (self.next_long() as u64 >> (64 - 24)) as f32 * 5.960_464_5e-8
+    }
+
+    /// Gets the next random double in `0.0..1.0`
+    pub fn next_double(&mut self) -> f64 {
+        (self.next_long() as u64 >> (64 - 53)) as f64 * 1.110_223_024_625_156_5e-16
+    }
+
+    /// Advances the generator by `n` calls of [`Self::next_long()`]
+    pub fn skip(&mut self, n: u64) {
+        for _ in 0..n {
+            self.next_long();
+        }
+    }
+}
+
+/// The mixing function used to expand a 64 bit seed into 128 bits
+fn mix_stafford_13(mut value: u64) -> u64 {
+    value = (value ^ (value >> 30)).wrapping_mul(0xbf58476d1ce4e5b9);
+    value = (value ^ (value >> 27)).wrapping_mul(0x94d049bb133111eb);
+    value ^ (value >> 31)
+}
@@ -0,0 +1,291 @@
+//! Types for the different kinds of minecraft seeds
+//!
+//! A minecraft world is generated from a 64 bit [`WorldSeed`]. Many parts of
+//! world generation, like structure positions, only use the lower 48 bits of
+//! the seed, which are called the [`StructureSeed`]. Each structure seed is
+//! shared by 65536 world seeds, which differ only in their upper 16 bits.
+//! These are called sister seeds and can be iterated with
+//! [`StructureSeed::sister_seeds()`].
+//!
+//! Seeds typed in by players can be parsed with [`WorldSeed::parse()`] or
+//! [`str::parse()`], which follow the same rules as the seed field in game.
+//!
+//! # Usage
+//! Searching structure seeds first and biomes second is a lot faster than
+//! checking every world seed:
+//! ```
+#![doc = include_str!("../examples/efficient_structure_hunting.rs")]
+//! ```
+
+use std::{
+    collections::hash_map::RandomState,
+    fmt::Display,
+    hash::{BuildHasher, Hasher},
+    iter::FusedIterator,
+    str::FromStr,
+};
+
+use thiserror::Error;
+
+/// A full 64 bit minecraft world seed
+///
+/// This is the seed shown by `/seed` in game.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord, Default)]
+pub struct WorldSeed(i64);
+
+/// The lower 48 bits of a [`WorldSeed`]
+///
+/// Structure generation attempts only depend on the structure seed, see
+/// [`crate::structures::StructureRegion`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord, Default)]
+pub struct StructureSeed(u64);
+
+/// What to do when parsing a seed from empty text
+///
+/// In game an empty seed field generates a world with a random seed.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum EmptySeed {
+    /// Return [`SeedParseError::Empty`]
+    Error,
+    /// Pick a random seed, like the game does
+    Random,
+}
+
+/// An error parsing a [`WorldSeed`] from text
+#[derive(Error, Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SeedParseError {
+    /// The text was empty or only contained whitespace
+    #[error("the seed is empty")]
+    Empty,
+}
+
+impl WorldSeed {
+    /// Creates a new world seed
+    #[inline]
+    #[must_use]
+    pub const fn new(seed: i64) -> Self {
+        Self(seed)
+    }
+
+    /// Gets the seed as a number
+    #[inline]
+    #[must_use]
+    pub const fn get(self) -> i64 {
+        self.0
+    }
+
+    /// Parses a seed from text the same way minecraft does
+    ///
+    /// Leading and trailing whitespace is ignored. If the rest of the text is
+    /// a number which fits in an [`i64`] it is used as is, otherwise the seed
+    /// is the java `String.hashCode()` of the text. What happens with empty
+    /// text is decided by `empty`.
+    ///
+    /// # Errors
+    /// Returns [`SeedParseError::Empty`] if the text is empty and `empty` is
+    /// [`EmptySeed::Error`]
+    ///
+    /// # Examples
+    /// ```
+    /// use cubiomes::seed::{EmptySeed, WorldSeed};
+    ///
+    /// assert_eq!(WorldSeed::parse("  42 ", EmptySeed::Error), Ok(WorldSeed::new(42)));
+    /// assert_eq!(WorldSeed::parse("glacier", EmptySeed::Error), Ok(WorldSeed::new(108181935)));
+    ///
+    /// // Too large to be an i64, so it is hashed like text
+    /// assert_eq!(
+    ///     WorldSeed::parse("9223372036854775808", EmptySeed::Error),
+    ///     Ok(WorldSeed::new(-1773151197))
+    /// );
+    ///
+    /// assert!(WorldSeed::parse("", EmptySeed::Random).is_ok());
+    /// ```
+    pub fn parse(text: &str, empty: EmptySeed) -> Result<Self, SeedParseError> {
+        // Java's String.trim() removes every char up to and including space
+        let text = text.trim_matches(|c: char| c <= ' ');
+
+        if text.is_empty() {
+            return match empty {
+                EmptySeed::Error => Err(SeedParseError::Empty),
+                EmptySeed::Random => Ok(Self::random()),
+            };
+        }
+
+        Ok(Self(
+            text.parse::<i64>()
+                .unwrap_or_else(|_| java_string_hash(text) as i64),
+        ))
+    }
+
+    /// Picks a random seed
+    fn random() -> Self {
+        // Every RandomState is randomly keyed, so hashing nothing gives a
+        // random number without depending on an rng crate
+        Self(RandomState::new().build_hasher().finish() as i64)
+    }
+
+    /// Gets the structure seed of this world seed, eg. its lower 48 bits
+    #[inline]
+    #[must_use]
+    pub const fn structure_seed(self) -> StructureSeed {
+        StructureSeed::new(self.0)
+    }
+
+    /// Gets the upper 16 bits of this world seed
+    ///
+    /// Together with the [`StructureSeed`] they make up the world seed, see
+    /// [`StructureSeed::with_upper_bits()`].
+    #[inline]
+    #[must_use]
+    pub const fn upper_bits(self) -> u16 {
+        (self.0 as u64 >> StructureSeed::BITS) as u16
+    }
+}
+
+impl StructureSeed {
+    /// Amount of bits in a structure seed
+    pub const BITS: u32 = 48;
+
+    /// Mask of the bits of a world seed which make up the structure seed
+    pub const MASK: u64 = (1 << Self::BITS) - 1;
+
+    /// Creates a new structure seed from the lower 48 bits of `seed`
+    ///
+    /// The upper 16 bits are ignored.
+    #[inline]
+    #[must_use]
+    pub const fn new(seed: i64) -> Self {
+        Self(seed as u64 & Self::MASK)
+    }
+
+    /// Gets the seed as a number
+    ///
+    /// The number is always positive and smaller than 2^48.
+    #[inline]
+    #[must_use]
+    pub const fn get(self) -> i64 {
+        self.0 as i64
+    }
+
+    /// Combines the structure seed with the upper 16 bits into a world seed
+    #[inline]
+    #[must_use]
+    pub const fn with_upper_bits(self, upper_bits: u16) -> WorldSeed {
+        WorldSeed(((upper_bits as u64) << Self::BITS | self.0) as i64)
+    }
+
+    /// Iterates over the 65536 world seeds sharing this structure seed
+    ///
+    /// The seeds are iterated in the order of their upper 16 bits, starting
+    /// with the seed whose upper bits are zero.
+    ///
+    /// # Examples
+    /// ```
+    /// use cubiomes::seed::StructureSeed;
+    ///
+    /// let structure_seed = StructureSeed::new(123456789);
+    ///
+    /// assert_eq!(structure_seed.sister_seeds().len(), 65536);
+    ///
+    /// for world_seed in structure_seed.sister_seeds() {
+    ///     assert_eq!(world_seed.structure_seed(), structure_seed);
+    /// }
+    /// ```
+    #[inline]
+    #[must_use]
+    pub const fn sister_seeds(self) -> SisterSeeds {
+        SisterSeeds {
+            structure_seed: self,
+            next: 0,
+        }
+    }
+}
+
+/// Computes java's `String.hashCode()`, which hashes the UTF-16 code units
+fn java_string_hash(text: &str) -> i32 {
+    text.encode_utf16().fold(0i32, |hash, unit| {
+        hash.wrapping_mul(31).wrapping_add(unit as i32)
+    })
+}
+
+/// Parses the seed like [`WorldSeed::parse()`] with [`EmptySeed::Error`]
+impl FromStr for WorldSeed {
+    type Err = SeedParseError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Self::parse(s, EmptySeed::Error)
+    }
+}
+
+impl From<i64> for WorldSeed {
+    fn from(value: i64) -> Self {
+        Self::new(value)
+    }
+}
+
+impl From<WorldSeed> for i64 {
+    fn from(value: WorldSeed) -> Self {
+        value.get()
+    }
+}
+
+impl From<WorldSeed> for StructureSeed {
+    fn from(value: WorldSeed) -> Self {
+        value.structure_seed()
+    }
+}
+
+impl From<StructureSeed> for i64 {
+    fn from(value: StructureSeed) -> Self {
+        value.get()
+    }
+}
+
+impl Display for WorldSeed {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.0)
+    }
+}
+
+impl Display for StructureSeed {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.0)
+    }
+}
+
+/// Iterator over the world seeds sharing a [`StructureSeed`]
+///
+/// Created with [`StructureSeed::sister_seeds()`].
+#[derive(Debug, Clone)]
+pub struct SisterSeeds {
+    structure_seed: StructureSeed,
+    next: u32,
+}
+
+impl SisterSeeds {
+    const COUNT: u32 = 1 << (64 - StructureSeed::BITS);
+}
+
+impl Iterator for SisterSeeds {
+    type Item = WorldSeed;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.next >= Self::COUNT {
+            return None;
+        }
+
+        let seed = self.structure_seed.with_upper_bits(self.next as u16);
+        self.next += 1;
+
+        Some(seed)
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        let remaining = (Self::COUNT - self.next) as usize;
+        (remaining, Some(remaining))
+    }
+}
+
+impl ExactSizeIterator for SisterSeeds {}
+
+impl FusedIterator for SisterSeeds {}
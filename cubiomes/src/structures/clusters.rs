@@ -0,0 +1,325 @@
+//! Module containing [StructureCluster], used for finding groups of
+//! structures which can all be reached from one spot
+//!
+//! This is useful for finding multi-structure farms, for example double, triple
+//! or quad witch huts. See [`Generator::find_structure_clusters()`] for usage.
+
+use super::StructureGenerationError;
+use crate::generator::{BlockPosition, Generator, Range};
+use cubiomes_sys::enums::StructureType;
+
+/// Maximum amount of neighbours considered for one cluster.
+///
+/// Finding the largest cluster tries every combination of neighbours, so this
+/// keeps the search from exploding if the radius is huge compared to the
+/// structure spacing.
+const MAX_NEIGHBOURS: usize = 12;
+
+/// A group of structures which can all be reached from a single position
+#[derive(Debug, Clone, PartialEq)]
+pub struct StructureCluster {
+    /// The position from which all the structures in the cluster are reachable
+    pub center: BlockPosition,
+    /// Distance in blocks from [`Self::center`] to the farthest structure in
+    /// the cluster
+    pub radius: f64,
+    /// Positions of the structures in the cluster
+    pub structures: Vec<BlockPosition>,
+}
+
+impl Generator {
+    /// Finds groups of structures which can all be covered from one spot
+    ///
+    /// The search generates every structure of the given type inside `area`
+    /// and groups them into clusters of at least `min_count` structures,
+    /// where every structure is at most `max_radius` blocks from the center of
+    /// the cluster. The reported center is the block nearest to the center of
+    /// the smallest circle enclosing the cluster which still has every
+    /// structure within `max_radius`, so groups which only fit around a
+    /// center between blocks are not reported.
+    ///
+    /// The structures are sorted by position, and each one is used as the
+    /// first structure of a cluster made of it and the structures after it.
+    /// Of those candidates, only the 12 closest to it within `2 * max_radius`
+    /// are considered, so a group of more than 13 structures is reported as
+    /// several smaller clusters. Clusters which only contain structures of an
+    /// already found cluster are skipped, but clusters overlapping each other
+    /// are all reported, so a structure can be in more than one cluster.
+    ///
+    /// Only structures that are placed on the region grid can be searched for.
+    /// See [`super::StructureRegion`] for details.
+    ///
+    /// # Examples
+    /// ```
+    /// use cubiomes::enums::{Dimension, MCVersion, StructureType};
+    /// use cubiomes::generator::{Generator, GeneratorFlags, Range, Scale};
+    ///
+    /// let mut generator = Generator::new(
+    ///     MCVersion::MC_1_21_WD,
+    ///     4239805798134,
+    ///     Dimension::DIM_OVERWORLD,
+    ///     GeneratorFlags::empty(),
+    /// );
+    ///
+    /// // Search for double witch huts within 10 000 blocks of spawn
+    /// let area = Range {
+    ///     scale: Scale::Block,
+    ///     x: -10_000,
+    ///     z: -10_000,
+    ///     size_x: 20_000,
+    ///     size_z: 20_000,
+    ///     y: 0,
+    ///     size_y: 0,
+    /// };
+    ///
+    /// let clusters = generator
+    ///     .find_structure_clusters(StructureType::Swamp_Hut, area, 128.0, 2)
+    ///     .expect("swamp huts exist in 1.21");
+    ///
+    /// for cluster in clusters {
+    ///     println!("{} huts around {:?}", cluster.structures.len(), cluster.center);
+    /// }
+    /// ```
+    pub fn find_structure_clusters(
+        &mut self,
+        structure_type: StructureType,
+        area: Range,
+        max_radius: f64,
+        min_count: usize,
+    ) -> Result<Vec<StructureCluster>, StructureGenerationError> {
+        let positions = self.structures_in_range(structure_type, area)?;
+
+        Ok(group_into_clusters(&positions, max_radius, min_count))
+    }
+}
+
+/// Groups sorted positions into clusters
+///
+/// Every cluster is searched for starting from its smallest position, so each
+/// group of structures is only found once.
+pub(super) fn group_into_clusters(
+    positions: &[BlockPosition],
+    max_radius: f64,
+    min_count: usize,
+) -> Vec<StructureCluster> {
+    let mut clusters: Vec<StructureCluster> = Vec::new();
+
+    for (idx, anchor) in positions.iter().enumerate() {
+        let mut neighbours: Vec<BlockPosition> = positions[idx + 1..]
+            .iter()
+            .copied()
+            .filter(|pos| anchor.distance_to(*pos) <= 2.0 * max_radius)
+            .collect();
+
+        if neighbours.len() + 1 < min_count {
+            continue;
+        }
+
+        neighbours.sort_by(|a, b| anchor.distance_to(*a).total_cmp(&anchor.distance_to(*b)));
+        neighbours.truncate(MAX_NEIGHBOURS);
+
+        let Some(cluster) = largest_cluster(*anchor, &neighbours, max_radius) else {
+            continue;
+        };
+
+        if cluster.structures.len() < min_count {
+            continue;
+        }
+
+        let already_found = clusters.iter().any(|found| {
+            cluster
+                .structures
+                .iter()
+                .all(|pos| found.structures.contains(pos))
+        });
+
+        if !already_found {
+            clusters.push(cluster);
+        }
+    }
+
+    clusters
+}
+
+/// Finds the largest cluster containing the anchor and any of the neighbours
+fn largest_cluster(
+    anchor: BlockPosition,
+    neighbours: &[BlockPosition],
+    max_radius: f64,
+) -> Option<StructureCluster> {
+    let mut best: Option<(Vec<BlockPosition>, BlockPosition, f64)> = None;
+
+    for mask in 0u32..(1 << neighbours.len()) {
+        let members: Vec<BlockPosition> = std::iter::once(anchor)
+            .chain(
+                neighbours
+                    .iter()
+                    .enumerate()
+                    .filter(|(bit, _)| mask & (1 << bit) != 0)
+                    .map(|(_, pos)| *pos),
+            )
+            .collect();
+
+        if best
+            .as_ref()
+            .is_some_and(|(best_members, _, _)| best_members.len() > members.len())
+        {
+            continue;
+        }
+
+        let circle = enclosing_circle(&members);
+
+        if circle.radius > max_radius {
+            continue;
+        }
+
+        let Some((center, radius)) = block_center(&circle, &members, max_radius) else {
+            continue;
+        };
+
+        let is_better = best.as_ref().is_none_or(|(best_members, _, best_radius)| {
+            members.len() > best_members.len() || radius < *best_radius
+        });
+
+        if is_better {
+            best = Some((members, center, radius));
+        }
+    }
+
+    let (structures, center, radius) = best?;
+
+    Some(StructureCluster {
+        center,
+        radius,
+        structures,
+    })
+}
+
+/// Finds the block next to the center of the circle from which every member
+/// is within `max_radius`, along with the distance to the farthest member
+///
+/// Of the four blocks around the center, the one with the smallest distance
+/// to the farthest member is picked.
+fn block_center(
+    circle: &Circle,
+    members: &[BlockPosition],
+    max_radius: f64,
+) -> Option<(BlockPosition, f64)> {
+    let xs = [circle.x.floor() as i32, circle.x.ceil() as i32];
+    let zs = [circle.z.floor() as i32, circle.z.ceil() as i32];
+
+    xs.into_iter()
+        .flat_map(|x| zs.map(|z| BlockPosition::new(x, z)))
+        .map(|center| {
+            let radius = members
+                .iter()
+                .map(|pos| center.distance_to(*pos))
+                .fold(0.0, f64::max);
+
+            (center, radius)
+        })
+        .filter(|(_, radius)| *radius <= max_radius)
+        .min_by(|(_, a), (_, b)| a.total_cmp(b))
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub(super) struct Circle {
+    pub(super) x: f64,
+    pub(super) z: f64,
+    pub(super) radius: f64,
+}
+
+impl Circle {
+    fn from_two(a: (f64, f64), b: (f64, f64)) -> Self {
+        let x = (a.0 + b.0) / 2.0;
+        let z = (a.1 + b.1) / 2.0;
+
+        Self {
+            x,
+            z,
+            radius: (a.0 - x).hypot(a.1 - z),
+        }
+    }
+
+    fn from_three(a: (f64, f64), b: (f64, f64), c: (f64, f64)) -> Self {
+        let d = 2.0 * (a.0 * (b.1 - c.1) + b.0 * (c.1 - a.1) + c.0 * (a.1 - b.1));
+
+        // The points are on one line, so the circle is defined by the two
+        // points farthest apart
+        if d.abs() < f64::EPSILON {
+            let ab = Self::from_two(a, b);
+            let ac = Self::from_two(a, c);
+            let bc = Self::from_two(b, c);
+
+            return if ab.radius >= ac.radius && ab.radius >= bc.radius {
+                ab
+            } else if ac.radius >= bc.radius {
+                ac
+            } else {
+                bc
+            };
+        }
+
+        let a_sq = a.0 * a.0 + a.1 * a.1;
+        let b_sq = b.0 * b.0 + b.1 * b.1;
+        let c_sq = c.0 * c.0 + c.1 * c.1;
+
+        let x = (a_sq * (b.1 - c.1) + b_sq * (c.1 - a.1) + c_sq * (a.1 - b.1)) / d;
+        let z = (a_sq * (c.0 - b.0) + b_sq * (a.0 - c.0) + c_sq * (b.0 - a.0)) / d;
+
+        Self {
+            x,
+            z,
+            radius: (a.0 - x).hypot(a.1 - z),
+        }
+    }
+
+    fn contains(&self, point: (f64, f64)) -> bool {
+        (point.0 - self.x).hypot(point.1 - self.z) <= self.radius + 1e-7
+    }
+}
+
+/// Calculates the smallest circle enclosing all the given positions
+///
+/// # Panics
+/// Panics if positions is empty
+pub(super) fn enclosing_circle(positions: &[BlockPosition]) -> Circle {
+    let points: Vec<(f64, f64)> = positions
+        .iter()
+        .map(|pos| (pos.x as f64, pos.z as f64))
+        .collect();
+
+    let mut circle = Circle {
+        x: points[0].0,
+        z: points[0].1,
+        radius: 0.0,
+    };
+
+    for i in 1..points.len() {
+        if circle.contains(points[i]) {
+            continue;
+        }
+
+        circle = Circle {
+            x: points[i].0,
+            z: points[i].1,
+            radius: 0.0,
+        };
+
+        for j in 0..i {
+            if circle.contains(points[j]) {
+                continue;
+            }
+
+            circle = Circle::from_two(points[i], points[j]);
+
+            for k in 0..j {
+                if !circle.contains(points[k]) {
+                    circle = Circle::from_three(points[i], points[j], points[k]);
+                }
+            }
+        }
+    }
+
+    circle
+}
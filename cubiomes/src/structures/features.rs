@@ -0,0 +1,99 @@
+//! Module for small features that are placed per chunk
+//!
+//! Some entries of [`StructureType`], like desert wells and geodes, are not
+//! placed on a grid of regions like other structures. Instead each chunk has a
+//! small chance of containing one. See [`Generator::chunk_features()`] for
+//! finding them.
+
+use super::{StructureGenerationError, StructureRegion};
+use crate::generator::{BlockPosition, Generator, Range};
+use cubiomes_sys::enums::StructureType;
+
+/// Checks if the [`StructureType`] is a feature placed by chance in each chunk
+///
+/// Fossils are also placed this way in minecraft, but cubiomes does not
+/// support generating them, so there is no [`StructureType`] for them.
+#[must_use]
+pub fn is_chunk_feature(structure_type: StructureType) -> bool {
+    matches!(
+        structure_type,
+        StructureType::Desert_Well | StructureType::Geode
+    )
+}
+
+impl Generator {
+    /// Finds all the features of the given type inside the area
+    ///
+    /// Every chunk overlapping the area gets a chance based roll for the
+    /// feature, as minecraft does during chunk population. Positions that pass
+    /// the roll are then verified against the biomes of this generator, so
+    /// only features which would actually generate are returned.
+    ///
+    /// Only features for which [`is_chunk_feature()`] returns true are
+    /// supported, other structure types return
+    /// [`StructureGenerationError::NotAChunkFeature`].
+    ///
+    /// # Examples
+    /// ```
+    /// use cubiomes::enums::{Dimension, MCVersion, StructureType};
+    /// use cubiomes::generator::{Generator, GeneratorFlags, Range, Scale};
+    ///
+    /// let mut generator = Generator::new(
+    ///     MCVersion::MC_1_21_WD,
+    ///     4239805798134,
+    ///     Dimension::DIM_OVERWORLD,
+    ///     GeneratorFlags::empty(),
+    /// );
+    ///
+    /// // Find the geodes within a 64 by 64 chunk area
+    /// let area = Range {
+    ///     scale: Scale::Chunk,
+    ///     x: -32,
+    ///     z: -32,
+    ///     size_x: 64,
+    ///     size_z: 64,
+    ///     y: 0,
+    ///     size_y: 0,
+    /// };
+    ///
+    /// let geodes = generator
+    ///     .chunk_features(StructureType::Geode, area)
+    ///     .expect("geodes exist in 1.21");
+    ///
+    /// println!("Found geodes at: {geodes:?}");
+    /// ```
+    pub fn chunk_features(
+        &mut self,
+        feature: StructureType,
+        area: Range,
+    ) -> Result<Vec<BlockPosition>, StructureGenerationError> {
+        if !is_chunk_feature(feature) {
+            return Err(StructureGenerationError::NotAChunkFeature(feature));
+        }
+
+        let min = area.min_block_position();
+        let max = area.max_block_position();
+
+        let (min_chunk_x, min_chunk_z) = min.scale_by_num(16);
+        let (max_chunk_x, max_chunk_z) = BlockPosition::new(max.x - 1, max.z - 1).scale_by_num(16);
+
+        // Chance based features use the chunk as their region
+        let mut chunk = StructureRegion::new(0, 0, self.minecraft_version()?, feature)?;
+
+        let mut positions = Vec::new();
+
+        for x in min_chunk_x..=max_chunk_x {
+            for z in min_chunk_z..=max_chunk_z {
+                (chunk.x, chunk.z) = (x, z);
+
+                if let Some(pos) = self.try_generate_structure_in_region(chunk)? {
+                    if (min.x..max.x).contains(&pos.x) && (min.z..max.z).contains(&pos.z) {
+                        positions.push(pos);
+                    }
+                }
+            }
+        }
+
+        Ok(positions)
+    }
+}
@@ -6,14 +6,25 @@
 //! Notably stronghold generation follows an iterative method instead. For
 //! generating positions of strongholds, see [`strongholds::StrongholdIter`] and
 //! [`crate::generator::Generator::strongholds()`].
+//!
+//! For finding groups of structures close to each other, such as quad witch
+//! huts, see [`clusters`].
+//!
+//! Small features like desert wells and geodes are placed by chance in each
+//! chunk instead. For finding them see [`features`].
 
-use crate::generator::{BlockPosition, Generator};
+use crate::{
+    generator::{error::GeneratorError, BlockPosition, Generator, Range},
+    seed::{StructureSeed, WorldSeed},
+};
 use bitflags::bitflags;
 use cubiomes_sys::enums::{self};
 use enums::StructureType;
-use std::mem::{transmute, MaybeUninit};
+use std::mem::MaybeUninit;
 use thiserror::Error;
 
+pub mod clusters;
+pub mod features;
 pub mod strongholds;
 #[cfg(test)]
 mod test;
@@ -25,6 +36,39 @@ pub enum StructureGenerationError {
     /// bug. Please report it on github
     #[error("Underlying library cubiomes returned a bool that is not 0 or 1.")]
     CubiomesError,
+    /// The structure type is not a feature placed by chance in each chunk.
+    ///
+    /// See [`features::is_chunk_feature()`] for the supported features.
+    #[error("{0:?} is not a feature placed by chance in each chunk")]
+    NotAChunkFeature(StructureType),
+    /// The version of the [`StructureRegion`] doesn't match the generator
+    #[error("Structure region is for {region}, but the generator is for {generator}")]
+    VersionMismatch {
+        /// The minecraft version of the generator
+        generator: enums::MCVersion,
+        /// The minecraft version of the structure region
+        region: enums::MCVersion,
+    },
+    /// The generator is in an invalid state
+    #[error("Generator error: {0}")]
+    GeneratorError(GeneratorError),
+    /// The structure depends on the whole world seed, so a structure seed is
+    /// not enough to find its generation attempts
+    ///
+    /// See [`StructureRegion::depends_on_world_seed()`].
+    #[error("{structure_type:?} in {version} depends on the whole world seed")]
+    NeedsWorldSeed {
+        /// The structure type of the region
+        structure_type: StructureType,
+        /// The minecraft version of the region
+        version: enums::MCVersion,
+    },
+}
+
+impl From<GeneratorError> for StructureGenerationError {
+    fn from(value: GeneratorError) -> Self {
+        Self::GeneratorError(value)
+    }
 }
 
 // This is empty, since I dont know what flags cubiomes supports
@@ -35,25 +79,184 @@ impl Generator {
     /// Tries to get the [`BlockPosition`] of a structure inside of a
     /// [`StructureRegion`] with this generator.
     ///
-    /// # Panics
-    /// The function panics if the version of the structure region does not
-    /// match the generator
+    /// Returns `Ok(None)` if there is no structure in the region.
+    ///
+    /// # Errors
+    /// Returns [`StructureGenerationError::VersionMismatch`] if the version of
+    /// the structure region does not match the generator, or
+    /// [`StructureGenerationError::CubiomesError`] if verifying the structure
+    /// fails.
     pub fn try_generate_structure_in_region(
         &mut self,
         region_pos: StructureRegion,
-    ) -> Option<BlockPosition> {
-        assert_eq!(self.minecraft_version(), region_pos.minecraft_version);
+    ) -> Result<Option<BlockPosition>, StructureGenerationError> {
+        let version = self.minecraft_version()?;
 
-        let pos = self.get_structure_generation_attempt(region_pos)?;
+        if version != region_pos.minecraft_version {
+            return Err(StructureGenerationError::VersionMismatch {
+                generator: version,
+                region: region_pos.minecraft_version,
+            });
+        }
 
-        if self
-            .verify_structure_generation_attempt(pos, region_pos.structure_type)
-            .ok()?
-        {
-            return Some(pos);
+        let Some(pos) = self.get_structure_generation_attempt(region_pos) else {
+            return Ok(None);
+        };
+
+        if self.verify_structure_generation_attempt(pos, region_pos.structure_type)? {
+            return Ok(Some(pos));
+        }
+
+        Ok(None)
+    }
+
+    /// Finds the nearest structure of the given type to `center`
+    ///
+    /// This works like the in-game `/locate structure` command. The search
+    /// spirals outwards over the [`StructureRegion`]s around `center`, verifying
+    /// the generation attempt in each one. It stops as soon as no unchecked
+    /// region can contain a closer structure, or once every region within
+    /// `max_radius` blocks has been checked.
+    ///
+    /// Returns the position of the nearest structure together with its
+    /// distance from `center` in blocks, or `None` if there is no structure
+    /// within `max_radius`.
+    ///
+    /// # Examples
+    /// ```
+    /// use cubiomes::enums::{Dimension, MCVersion, StructureType};
+    /// use cubiomes::generator::{BlockPosition, Generator, GeneratorFlags};
+    ///
+    /// let mut generator = Generator::new(
+    ///     MCVersion::MC_1_21_WD,
+    ///     -5134222539607995087,
+    ///     Dimension::DIM_OVERWORLD,
+    ///     GeneratorFlags::empty(),
+    /// );
+    ///
+    /// let nearest = generator
+    ///     .locate_structure(StructureType::Village, BlockPosition::new(0, 0), 4096)
+    ///     .expect("villages exist in 1.21");
+    ///
+    /// if let Some((pos, distance)) = nearest {
+    ///     println!("Found a village at {pos:?}, {distance:.0} blocks away");
+    /// }
+    /// ```
+    pub fn locate_structure(
+        &mut self,
+        structure_type: StructureType,
+        center: BlockPosition,
+        max_radius: u32,
+    ) -> Result<Option<(BlockPosition, f64)>, StructureGenerationError> {
+        let version = self.minecraft_version()?;
+        let origin = StructureRegion::from_block_position(center, version, structure_type)?;
+
+        let region_size = origin.region_size_blocks() as i64;
+        let max_radius = max_radius as f64;
+        // Regions in ring n are at least n - 1 regions away from the center
+        let max_ring = (max_radius as i64 / region_size + 1) as i32;
+
+        let mut nearest: Option<(BlockPosition, f64)> = None;
+
+        for ring in 0..=max_ring {
+            let min_distance = ((ring - 1).max(0) as i64 * region_size) as f64;
+
+            if min_distance > max_radius || nearest.is_some_and(|(_, d)| d <= min_distance) {
+                break;
+            }
+
+            for (dx, dz) in ring_offsets(ring) {
+                let region = StructureRegion {
+                    x: origin.x + dx,
+                    z: origin.z + dz,
+                    ..origin
+                };
+
+                let Some(pos) = self.try_generate_structure_in_region(region)? else {
+                    continue;
+                };
+
+                let distance = center.distance_to(pos);
+
+                if distance <= max_radius && nearest.is_none_or(|(_, d)| distance < d) {
+                    nearest = Some((pos, distance));
+                }
+            }
+        }
+
+        Ok(nearest)
+    }
+
+    /// Generates every structure of the given type inside the range
+    ///
+    /// All the [`StructureRegion`]s overlapping the range are checked, and the
+    /// structures with a position inside the range are returned sorted by
+    /// position.
+    ///
+    /// # Errors
+    /// Returns [`StructureGenerationError::CubiomesError`] if verifying a
+    /// structure fails, or [`StructureGenerationError::GeneratorError`] if
+    /// the generator has an invalid version.
+    pub fn structures_in_range(
+        &mut self,
+        structure_type: StructureType,
+        area: Range,
+    ) -> Result<Vec<BlockPosition>, StructureGenerationError> {
+        let min = area.min_block_position();
+        let max = area.max_block_position();
+
+        let version = self.minecraft_version()?;
+
+        let first_region = StructureRegion::from_block_position(min, version, structure_type)?;
+        let last_region = StructureRegion::from_block_position(
+            BlockPosition::new(max.x - 1, max.z - 1),
+            version,
+            structure_type,
+        )?;
+
+        let mut positions = Vec::new();
+
+        for x in first_region.x..=last_region.x {
+            for z in first_region.z..=last_region.z {
+                let region = StructureRegion {
+                    x,
+                    z,
+                    ..first_region
+                };
+
+                if let Some(pos) = self.try_generate_structure_in_region(region)? {
+                    if (min.x..max.x).contains(&pos.x) && (min.z..max.z).contains(&pos.z) {
+                        positions.push(pos);
+                    }
+                }
+            }
         }
 
-        None
+        positions.sort();
+
+        Ok(positions)
+    }
+
+    /// Finds the world spawn point of the generator
+    ///
+    /// This searches for a suitable spawn biome like the game does, so it
+    /// is quite slow. The spawn is only found for the overworld.
+    ///
+    /// # Errors
+    /// Returns [`GeneratorError::WrongDimension`] if the generator is not in
+    /// the overworld.
+    pub fn spawn(&self) -> Result<BlockPosition, GeneratorError> {
+        let dimension = self.dimension()?;
+
+        if dimension != enums::Dimension::DIM_OVERWORLD {
+            return Err(GeneratorError::WrongDimension {
+                expected: enums::Dimension::DIM_OVERWORLD,
+                actual: dimension,
+            });
+        }
+
+        // SAFETY: The generator is valid and initialized for the overworld
+        Ok(unsafe { cubiomes_sys::getSpawn(self.as_ptr()) }.into())
     }
 
     /// Used to verify a structure generation attempt
@@ -85,8 +288,7 @@ impl Generator {
         &self,
         region_pos: StructureRegion,
     ) -> Option<BlockPosition> {
-        let seed = self.seed();
-        region_pos.get_structure_generation_attempt(seed)
+        region_pos.get_structure_generation_attempt_with_world_seed(WorldSeed::new(self.seed()))
     }
 }
 
@@ -120,14 +322,16 @@ impl Generator {
 /// ## Finding a seed with a specific structure at spawn
 ///
 /// It should be noted, that only the lower 48 bits of the seed affect
-/// the positions of structure generation attempts. Generating the position of
+/// the positions of structure generation attempts, except for the structures
+/// listed in [`Self::depends_on_world_seed()`]. Generating the position of
 /// a structure generation attempt is also cheaper than verifying the biome for
 /// a structure.
 ///
 /// So if you for example, want to find a seed with a specific set of structures
 /// near spawn, you should try to find it by modifying the 48 bottom bits and
 /// that an attempt exist in your wanted region. Once you've found the attempts,
-/// you can modify the top 16 bits until the biomes match. This example
+/// you can modify the top 16 bits until the biomes match. See
+/// [`crate::seed`] for the seed types used for this. This example
 /// demonstrates how to achieve this.
 /// ```
 #[doc = include_str!("../../examples/efficient_structure_hunting.rs")]
@@ -203,23 +407,72 @@ impl StructureRegion {
 
     /// Tries to get the [`BlockPosition`] of a generation attempt for self
     ///
-    /// Check [self] for what a generation attempt means
+    /// Check [self] for what a generation attempt means. For most structures
+    /// generation attempts only depend on the [`StructureSeed`], so they are
+    /// the same for all its sister seeds.
+    ///
+    /// # Errors
+    /// Returns [`StructureGenerationError::NeedsWorldSeed`] if the attempts of
+    /// [self] depend on the whole world seed, see
+    /// [`Self::depends_on_world_seed()`]. Use
+    /// [`Self::get_structure_generation_attempt_with_world_seed()`] for them.
+    pub fn get_structure_generation_attempt(
+        &self,
+        seed: StructureSeed,
+    ) -> Result<Option<BlockPosition>, StructureGenerationError> {
+        if self.depends_on_world_seed() {
+            return Err(StructureGenerationError::NeedsWorldSeed {
+                structure_type: self.structure_type,
+                version: self.minecraft_version,
+            });
+        }
+
+        Ok(self.generation_attempt(seed.get() as u64))
+    }
+
+    /// Tries to get the [`BlockPosition`] of a generation attempt for self
+    /// with the whole world seed
+    ///
+    /// This works for every structure type, including the ones for which
+    /// [`Self::depends_on_world_seed()`] is true.
+    #[must_use]
+    pub fn get_structure_generation_attempt_with_world_seed(
+        &self,
+        seed: WorldSeed,
+    ) -> Option<BlockPosition> {
+        self.generation_attempt(seed.get() as u64)
+    }
+
+    /// Checks if the generation attempts of [self] depend on the whole world
+    /// seed instead of only the [`StructureSeed`]
+    ///
+    /// Since 1.18 desert wells, geodes, end gateways and mineshafts are seeded
+    /// with the population seed of their chunk, which uses all 64 bits of the
+    /// world seed. Sister seeds share the attempts of all other structures.
     #[must_use]
-    pub fn get_structure_generation_attempt(&self, seed: i64) -> Option<BlockPosition> {
+    pub fn depends_on_world_seed(&self) -> bool {
+        self.minecraft_version >= enums::MCVersion::MC_1_18
+            && matches!(
+                self.structure_type,
+                StructureType::Desert_Well
+                    | StructureType::Geode
+                    | StructureType::End_Gateway
+                    | StructureType::Mineshaft
+            )
+    }
+
+    fn generation_attempt(&self, seed: u64) -> Option<BlockPosition> {
         let minecraft_version = self.minecraft_version;
 
         let mut pos: MaybeUninit<cubiomes_sys::Pos> = MaybeUninit::uninit();
 
         // SAFETY:
         // The ffi function receives correct input data
-        //
-        // The seed is transmuted as cubiomes wants it as u64
-        // even though minecraft uses signed integers
         if unsafe {
             cubiomes_sys::getStructurePos(
                 self.structure_type as i32,
                 minecraft_version as i32,
-                transmute::<i64, u64>(seed),
+                seed,
                 self.x,
                 self.z,
                 pos.as_mut_ptr(),
@@ -269,6 +522,13 @@ impl StructureRegion {
     }
 }
 
+/// Offsets of the regions on the edge of a square ring around the origin
+fn ring_offsets(ring: i32) -> impl Iterator<Item = (i32, i32)> {
+    (-ring..=ring)
+        .flat_map(move |dx| (-ring..=ring).map(move |dz| (dx, dz)))
+        .filter(move |(dx, dz)| dx.abs() == ring || dz.abs() == ring)
+}
+
 fn get_structure_scale(
     structure_type: enums::StructureType,
     minecraft_version: enums::MCVersion,
@@ -37,7 +37,7 @@ impl<'generator> Generator {
         unsafe {
             cubiomes_sys::initFirstStronghold(
                 sh_iter.as_mut_ptr(),
-                self.minecraft_version() as i32,
+                (*self.as_ptr()).mc,
                 transmute::<i64, u64>(self.seed()),
             );
         }
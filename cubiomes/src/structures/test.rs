@@ -1,8 +1,15 @@
 use crate::enums::*;
-use crate::generator::{Generator, GeneratorFlags};
+use crate::generator::error::GeneratorError;
+use crate::generator::{BlockPosition, Generator, GeneratorFlags, Range, Scale};
+use crate::seed::WorldSeed;
+use crate::structures::clusters::{enclosing_circle, group_into_clusters, StructureCluster};
 use crate::structures::strongholds::StrongholdIter;
+use crate::structures::StructureGenerationError;
+use crate::structures::StructureRegion;
+use std::mem::MaybeUninit;
 
 #[test]
+#[cfg_attr(miri, ignore)]
 fn iterate_over_limit() {
     let generator = Generator::new(
         MCVersion::MC_1_21_WD,
@@ -20,3 +27,371 @@ fn iterate_over_limit() {
         iter.next();
     }
 }
+
+#[test]
+#[cfg_attr(miri, ignore)]
+fn locate_nearest_outpost() {
+    let mut generator = Generator::new(
+        MCVersion::MC_1_21_WD,
+        -5134222539607995087,
+        Dimension::DIM_OVERWORLD,
+        GeneratorFlags::empty(),
+    );
+
+    let center = BlockPosition::new(3888, 2656);
+    let known_outpost = generator
+        .try_generate_structure_in_region(
+            StructureRegion::from_block_position(
+                center,
+                MCVersion::MC_1_21_WD,
+                StructureType::Outpost,
+            )
+            .expect("outposts exist in 1.21"),
+        )
+        .expect("region matches the generator")
+        .expect("there is an outpost in the region of the center");
+
+    let (pos, distance) = generator
+        .locate_structure(StructureType::Outpost, center, 2048)
+        .expect("outposts exist in 1.21")
+        .expect("there is an outpost within the radius");
+
+    assert!(distance <= center.distance_to(known_outpost));
+    assert_eq!(distance, center.distance_to(pos));
+}
+
+#[test]
+#[cfg_attr(miri, ignore)]
+fn locate_respects_max_radius() {
+    let mut generator = Generator::new(
+        MCVersion::MC_1_21_WD,
+        -5134222539607995087,
+        Dimension::DIM_OVERWORLD,
+        GeneratorFlags::empty(),
+    );
+
+    let center = BlockPosition::new(3888, 2656);
+    let (nearest, distance) = generator
+        .locate_structure(StructureType::Outpost, center, 2048)
+        .expect("outposts exist in 1.21")
+        .expect("there is an outpost within the radius");
+
+    let covering = distance.ceil() as u32;
+
+    assert_eq!(
+        generator
+            .locate_structure(StructureType::Outpost, center, covering)
+            .expect("outposts exist in 1.21"),
+        Some((nearest, distance))
+    );
+    assert_eq!(
+        generator
+            .locate_structure(StructureType::Outpost, center, covering - 1)
+            .expect("outposts exist in 1.21"),
+        None
+    );
+}
+
+#[test]
+fn enclosing_circle_of_square() {
+    let circle = enclosing_circle(&[
+        BlockPosition::new(0, 0),
+        BlockPosition::new(100, 0),
+        BlockPosition::new(0, 100),
+        BlockPosition::new(100, 100),
+    ]);
+
+    assert_eq!((circle.x, circle.z), (50.0, 50.0));
+    assert!((circle.radius - 50.0 * 2f64.sqrt()).abs() < 1e-9);
+}
+
+#[test]
+fn enclosing_circle_of_collinear_points() {
+    let circle = enclosing_circle(&[
+        BlockPosition::new(0, 0),
+        BlockPosition::new(10, 0),
+        BlockPosition::new(-30, 0),
+    ]);
+
+    assert_eq!((circle.x, circle.z, circle.radius), (-10.0, 0.0, 20.0));
+}
+
+#[test]
+fn overlapping_clusters_are_all_reported() {
+    let positions = [
+        BlockPosition::new(0, 0),
+        BlockPosition::new(200, 0),
+        BlockPosition::new(400, 0),
+    ];
+
+    // The middle structure is within reach of both the others, but the outer
+    // ones are too far apart for one cluster
+    assert_eq!(
+        group_into_clusters(&positions, 100.0, 2),
+        [
+            StructureCluster {
+                center: BlockPosition::new(100, 0),
+                radius: 100.0,
+                structures: positions[..2].to_vec(),
+            },
+            StructureCluster {
+                center: BlockPosition::new(300, 0),
+                radius: 100.0,
+                structures: positions[1..].to_vec(),
+            },
+        ]
+    );
+
+    // A cluster inside an already found one is skipped
+    assert_eq!(
+        group_into_clusters(&positions, 200.0, 2),
+        [StructureCluster {
+            center: BlockPosition::new(200, 0),
+            radius: 200.0,
+            structures: positions.to_vec(),
+        }]
+    );
+}
+
+#[test]
+#[cfg_attr(miri, ignore)]
+fn swamp_hut_clusters_are_within_radius() {
+    let mut generator = Generator::new(
+        MCVersion::MC_1_21_WD,
+        4239805798134,
+        Dimension::DIM_OVERWORLD,
+        GeneratorFlags::empty(),
+    );
+
+    let area = Range {
+        scale: Scale::Block,
+        x: -10_000,
+        z: -10_000,
+        size_x: 20_000,
+        size_z: 20_000,
+        y: 0,
+        size_y: 0,
+    };
+
+    let clusters = generator
+        .find_structure_clusters(StructureType::Swamp_Hut, area, 128.0, 2)
+        .expect("swamp huts exist in 1.21");
+
+    assert!(!clusters.is_empty());
+
+    for cluster in clusters {
+        assert!(cluster.structures.len() >= 2);
+        assert!(cluster.radius <= 128.0);
+
+        for pos in cluster.structures {
+            assert!(area.is_inside(pos.x, pos.z));
+            assert!(cluster.center.distance_to(pos) <= cluster.radius);
+        }
+    }
+}
+
+#[test]
+#[cfg_attr(miri, ignore)]
+fn geodes_are_inside_area_and_viable() {
+    let mut generator = Generator::new(
+        MCVersion::MC_1_21_WD,
+        4239805798134,
+        Dimension::DIM_OVERWORLD,
+        GeneratorFlags::empty(),
+    );
+
+    let area = Range {
+        scale: Scale::Block,
+        x: -500,
+        z: -500,
+        size_x: 1000,
+        size_z: 1000,
+        y: 0,
+        size_y: 0,
+    };
+
+    let geodes = generator
+        .chunk_features(StructureType::Geode, area)
+        .expect("geodes exist in 1.21");
+
+    // About one in 24 chunks has a geode
+    assert!(!geodes.is_empty());
+
+    for geode in geodes {
+        assert!(area.is_inside(geode.x, geode.z));
+        assert!(generator
+            .verify_structure_generation_attempt(geode, StructureType::Geode)
+            .expect("geodes exist in 1.21"));
+    }
+}
+
+#[test]
+#[cfg_attr(miri, ignore)]
+fn region_structures_are_not_chunk_features() {
+    let mut generator = Generator::new(
+        MCVersion::MC_1_21_WD,
+        4239805798134,
+        Dimension::DIM_OVERWORLD,
+        GeneratorFlags::empty(),
+    );
+
+    let area = Range {
+        scale: Scale::Chunk,
+        x: 0,
+        z: 0,
+        size_x: 4,
+        size_z: 4,
+        y: 0,
+        size_y: 0,
+    };
+
+    assert_eq!(
+        generator.chunk_features(StructureType::Village, area),
+        Err(StructureGenerationError::NotAChunkFeature(
+            StructureType::Village
+        ))
+    );
+}
+
+#[test]
+#[cfg_attr(miri, ignore)]
+fn villages_in_range_are_sorted_and_inside() {
+    let mut generator = Generator::new(
+        MCVersion::MC_1_21_WD,
+        4239805798134,
+        Dimension::DIM_OVERWORLD,
+        GeneratorFlags::empty(),
+    );
+
+    let area = Range {
+        scale: Scale::Chunk,
+        x: -100,
+        z: -100,
+        size_x: 200,
+        size_z: 200,
+        y: 0,
+        size_y: 0,
+    };
+
+    let villages = generator
+        .structures_in_range(StructureType::Village, area)
+        .expect("villages exist in 1.21");
+
+    assert!(!villages.is_empty());
+    assert!(villages.is_sorted());
+
+    for village in villages {
+        assert!(area.is_inside(village.x, village.z));
+    }
+}
+
+#[test]
+#[cfg_attr(miri, ignore)]
+fn spawn_is_only_in_the_overworld() {
+    let generator = Generator::new(
+        MCVersion::MC_1_21_WD,
+        4239805798134,
+        Dimension::DIM_OVERWORLD,
+        GeneratorFlags::empty(),
+    );
+
+    let spawn = generator
+        .spawn()
+        .expect("the generator is in the overworld");
+    // The spawn is searched for close to the origin
+    assert!(spawn.distance_to(BlockPosition::new(0, 0)) < 2048.0);
+
+    let generator = Generator::new(
+        MCVersion::MC_1_21_WD,
+        4239805798134,
+        Dimension::DIM_NETHER,
+        GeneratorFlags::empty(),
+    );
+
+    assert_eq!(
+        generator.spawn(),
+        Err(GeneratorError::WrongDimension {
+            expected: Dimension::DIM_OVERWORLD,
+            actual: Dimension::DIM_NETHER,
+        })
+    );
+}
+
+/// Gets a generation attempt straight from cubiomes with the given seed
+fn cubiomes_attempt(region: StructureRegion, seed: i64) -> Option<BlockPosition> {
+    let mut pos: MaybeUninit<cubiomes_sys::Pos> = MaybeUninit::uninit();
+
+    // SAFETY: getStructurePos only writes to pos
+    let found = unsafe {
+        cubiomes_sys::getStructurePos(
+            region.structure_type as i32,
+            region.minecraft_version as i32,
+            seed as u64,
+            region.x,
+            region.z,
+            pos.as_mut_ptr(),
+        )
+    } != 0;
+
+    // SAFETY: cubiomes initializes pos when it finds an attempt
+    found.then(|| unsafe { pos.assume_init() }.into())
+}
+
+#[test]
+#[cfg_attr(miri, ignore)]
+fn population_seeded_attempts_use_the_whole_seed() {
+    // The upper 16 bits of the seed are not zero
+    let seed = WorldSeed::new(-4804349703814383506);
+    let lower_bits = seed.structure_seed().get();
+
+    let mut generator = Generator::new(
+        MCVersion::MC_1_21_WD,
+        seed,
+        Dimension::DIM_OVERWORLD,
+        GeneratorFlags::empty(),
+    );
+
+    for structure_type in [StructureType::Geode, StructureType::Desert_Well] {
+        let mut upper_bits_matter = false;
+
+        for x in -32..32 {
+            for z in -32..32 {
+                let region = StructureRegion::new(x, z, MCVersion::MC_1_21_WD, structure_type)
+                    .expect("the feature exists in 1.21");
+                let expected = cubiomes_attempt(region, seed.get());
+
+                assert_eq!(
+                    region.get_structure_generation_attempt_with_world_seed(seed),
+                    expected
+                );
+
+                let generated = generator
+                    .try_generate_structure_in_region(region)
+                    .expect("region matches the generator");
+                assert!(generated.is_none() || generated == expected);
+
+                upper_bits_matter |= cubiomes_attempt(region, lower_bits) != expected;
+            }
+        }
+
+        assert!(upper_bits_matter, "{structure_type:?}");
+
+        let region = StructureRegion::new(0, 0, MCVersion::MC_1_21_WD, structure_type)
+            .expect("the feature exists in 1.21");
+
+        assert!(region.depends_on_world_seed());
+        assert_eq!(
+            region.get_structure_generation_attempt(seed.structure_seed()),
+            Err(StructureGenerationError::NeedsWorldSeed {
+                structure_type,
+                version: MCVersion::MC_1_21_WD,
+            })
+        );
+    }
+
+    // Before 1.18 the population seed only used the lower 48 bits
+    let region = StructureRegion::new(0, 0, MCVersion::MC_1_17_1, StructureType::Geode)
+        .expect("geodes exist in 1.17");
+
+    assert!(!region.depends_on_world_seed());
+}
@@ -1,8 +1,16 @@
 use crate::colors::{self, BiomeColorMap};
 use crate::enums::MCVersion;
-use crate::generator::{error::GeneratorError, Cache, Generator, GeneratorFlags, Range, Scale};
+use crate::error::{Error, ErrorContext, ResultExt};
+use crate::generator::{
+    error::{GeneratorError, TryFromRangeError},
+    Cache, Generator, GeneratorFlags, Range, Scale,
+};
+use crate::population::{carver_seed, decorator_seed, population_seed};
+use crate::rng::{JavaRandom, Xoroshiro};
+use crate::seed::{EmptySeed, SeedParseError, StructureSeed, WorldSeed};
+use crate::structures::{StructureGenerationError, StructureRegion};
 
-use cubiomes_sys::enums::{self, BiomeID, Dimension};
+use cubiomes_sys::enums::{self, BiomeID, Dimension, StructureType};
 use cubiomes_sys::num_traits::FromPrimitive;
 use std::ffi::CStr;
 
@@ -20,6 +28,7 @@ fn init_generator() -> Generator {
 }
 
 #[test]
+#[cfg_attr(miri, ignore)]
 fn biome_to_str_smoke() {
     let biome = enums::BiomeID::badlands;
     let version = MCVersion::MC_1_21_WD;
@@ -34,6 +43,7 @@ fn biome_to_str_smoke() {
 }
 
 #[test]
+#[cfg_attr(miri, ignore)]
 fn simple_biome_test() -> Result<(), GeneratorError> {
     let generator = init_generator();
 
@@ -45,6 +55,7 @@ fn simple_biome_test() -> Result<(), GeneratorError> {
 }
 
 #[test]
+#[cfg_attr(miri, ignore)]
 fn simple_biome_test_cached() -> Result<(), GeneratorError> {
     let mut generator = init_generator();
     generator.apply_seed(Dimension::DIM_OVERWORLD, -1693727681172482083);
@@ -101,6 +112,7 @@ fn test_range_outside_bounds() {
 }
 
 #[test]
+#[cfg_attr(miri, ignore)]
 fn init_biome_colors() {
     let colors = colors::new_biome_color_map();
 
@@ -110,6 +122,7 @@ fn init_biome_colors() {
 }
 
 #[test]
+#[cfg_attr(miri, ignore)]
 fn set_color() {
     let mut colors = BiomeColorMap::default();
 
@@ -119,6 +132,7 @@ fn set_color() {
 }
 
 #[test]
+#[cfg_attr(miri, ignore)]
 fn get_all_colors() {
     let biome_color_map = BiomeColorMap::new();
 
@@ -128,3 +142,516 @@ fn get_all_colors() {
         }
     }
 }
+
+#[test]
+#[cfg_attr(miri, ignore)]
+fn population_seed_at_origin_is_world_seed() {
+    for version in [
+        MCVersion::MC_B1_8,
+        MCVersion::MC_1_12_2,
+        MCVersion::MC_1_21_WD,
+    ] {
+        assert_eq!(population_seed(version, 5318008, 0, 0), 5318008);
+    }
+}
+
+#[test]
+#[cfg_attr(miri, ignore)]
+fn population_seed_uses_version_coordinates() {
+    // new Random(0).nextLong() is -4962768465676381896, which gets made odd
+    assert_eq!(
+        population_seed(MCVersion::MC_1_12_2, 0, 1, 0),
+        -4962768465676381895
+    );
+    // Since 1.13 the block coordinates are used instead of chunk coordinates
+    assert_eq!(
+        population_seed(MCVersion::MC_1_16_5, 0, 1, 0),
+        -5617319155983903856
+    );
+}
+
+#[test]
+fn decorator_seed_offsets() {
+    assert_eq!(decorator_seed(MCVersion::MC_1_21_WD, 100, 3, 2), 20103);
+    assert_eq!(decorator_seed(MCVersion::MC_1_12_2, 100, 3, 2), 100);
+}
+
+#[test]
+fn carver_seed_matches_java() {
+    assert_eq!(
+        carver_seed(MCVersion::MC_1_16_5, 0, 1, 0, 0),
+        -4962768465676381896
+    );
+    assert_eq!(carver_seed(MCVersion::MC_1_21_WD, 42, 0, 0, 3), 45);
+    // The carver index is only used since 1.13
+    assert_eq!(carver_seed(MCVersion::MC_1_12_2, 42, 0, 0, 3), 42);
+}
+
+#[test]
+#[cfg_attr(miri, ignore)]
+fn errors_carry_context() {
+    let generator = init_generator();
+    let range = Range {
+        scale: Scale::Quad,
+        x: 0,
+        z: 0,
+        size_x: 0,
+        size_z: 16,
+        y: 16,
+        size_y: 0,
+    };
+
+    let err = Cache::new(&generator, range)
+        .with_range(range)
+        .with_generator(&generator)
+        .expect_err("range has no width");
+
+    assert_eq!(
+        err.context(),
+        Some(&ErrorContext {
+            seed: Some(-4804349703814383506),
+            version: Some(MCVersion::MC_1_21_WD),
+            dimension: Some(Dimension::DIM_OVERWORLD),
+            range: Some(range),
+        })
+    );
+    assert_eq!(
+        err.without_context(),
+        &Error::Generator(GeneratorError::TryFromRangeError(
+            TryFromRangeError::XSizeOutOfBounds
+        ))
+    );
+
+    let message = err.to_string();
+    assert!(message.contains("-4804349703814383506"), "{message}");
+    assert!(message.contains("range 1:4 at x 0 z 0"), "{message}");
+}
+
+#[test]
+fn context_error_displays_source_once() {
+    let err = Error::from(TryFromRangeError::XSizeOutOfBounds).with_context(ErrorContext {
+        seed: Some(42),
+        ..ErrorContext::default()
+    });
+
+    let source = std::error::Error::source(&err).map(ToString::to_string);
+
+    assert_eq!(err.to_string(), "request failed with seed 42");
+    assert_eq!(
+        source,
+        Some(TryFromRangeError::XSizeOutOfBounds.to_string())
+    );
+}
+
+#[test]
+fn errors_convert_into_crate_error() {
+    assert_eq!(
+        Error::from(StructureGenerationError::CubiomesError),
+        Error::Structure(StructureGenerationError::CubiomesError)
+    );
+    assert_eq!(
+        Error::from(enums::ParseError::NotDimension),
+        Error::Parse(enums::ParseError::NotDimension)
+    );
+    assert_eq!(
+        Error::from(SeedParseError::Empty),
+        Error::Seed(SeedParseError::Empty)
+    );
+    assert_eq!(
+        Error::from(TryFromRangeError::ZSizeOutOfBounds).context(),
+        None
+    );
+}
+
+#[test]
+fn structure_seed_is_lower_48_bits() {
+    let world_seed = WorldSeed::new(-4804349703814383506);
+    let structure_seed = world_seed.structure_seed();
+
+    assert_eq!(
+        structure_seed.get(),
+        -4804349703814383506 & 0xffff_ffff_ffff
+    );
+    assert_eq!(
+        structure_seed.with_upper_bits(world_seed.upper_bits()),
+        world_seed
+    );
+    assert_eq!(StructureSeed::new(-1).get(), (1 << 48) - 1);
+}
+
+#[test]
+fn sister_seeds_share_structure_seed() {
+    let structure_seed = StructureSeed::new(5318008);
+    let sisters: Vec<WorldSeed> = structure_seed.sister_seeds().collect();
+
+    assert_eq!(sisters.len(), 65536);
+    assert_eq!(sisters[0], WorldSeed::new(5318008));
+    assert_eq!(sisters[65535].get(), 5318008 | (-1 << 48));
+    assert!(sisters
+        .iter()
+        .all(|seed| seed.structure_seed() == structure_seed));
+
+    let mut deduped = sisters.clone();
+    deduped.sort();
+    deduped.dedup();
+    assert_eq!(deduped.len(), 65536);
+}
+
+#[test]
+#[cfg_attr(miri, ignore)]
+fn sister_seeds_have_same_structure_attempts() {
+    let region = StructureRegion::new(3, -2, MCVersion::MC_1_21_WD, StructureType::Village)
+        .expect("villages exist in 1.21");
+    let structure_seed = StructureSeed::new(-4804349703814383506);
+    let attempt = region
+        .get_structure_generation_attempt(structure_seed)
+        .expect("villages only depend on the structure seed");
+
+    for seed in structure_seed.sister_seeds().step_by(4099) {
+        assert_eq!(
+            region.get_structure_generation_attempt(seed.structure_seed()),
+            Ok(attempt)
+        );
+        assert_eq!(
+            region.get_structure_generation_attempt_with_world_seed(seed),
+            attempt
+        );
+    }
+}
+
+#[test]
+fn numeric_seeds_parse_as_numbers() {
+    assert_eq!("42".parse(), Ok(WorldSeed::new(42)));
+    assert_eq!("  42 ".parse(), Ok(WorldSeed::new(42)));
+    assert_eq!("\t\n-7\u{1}".parse(), Ok(WorldSeed::new(-7)));
+    assert_eq!("+7".parse(), Ok(WorldSeed::new(7)));
+    assert_eq!("-0".parse(), Ok(WorldSeed::new(0)));
+    assert_eq!("-9223372036854775808".parse(), Ok(WorldSeed::new(i64::MIN)));
+}
+
+#[test]
+fn text_seeds_use_java_hash_code() {
+    assert_eq!("glacier".parse(), Ok(WorldSeed::new(108181935)));
+    assert_eq!("A".parse(), Ok(WorldSeed::new(65)));
+    // Wraps around like a java int and is sign extended
+    assert_eq!("Minecraft".parse(), Ok(WorldSeed::new(-1595926131)));
+    // Hashed as the two UTF-16 code units of the surrogate pair
+    assert_eq!("\u{1F600}".parse(), Ok(WorldSeed::new(1772899)));
+    // Out of range numbers and inner whitespace aren't numbers
+    assert_eq!(
+        "9223372036854775808".parse(),
+        Ok(WorldSeed::new(-1773151197))
+    );
+    assert_eq!("4 2".parse(), Ok(WorldSeed::new(51014)));
+}
+
+#[test]
+fn empty_seed_option() {
+    assert_eq!("".parse::<WorldSeed>(), Err(SeedParseError::Empty));
+    assert_eq!(
+        WorldSeed::parse(" \t ", EmptySeed::Error),
+        Err(SeedParseError::Empty)
+    );
+    assert!(WorldSeed::parse(" ", EmptySeed::Random).is_ok());
+}
+
+#[test]
+fn java_random_matches_java() {
+    assert_eq!(JavaRandom::new(0).next_long(), -4962768465676381896);
+    assert_eq!(JavaRandom::new(0).next_double(), 0.730967787376657);
+    assert_eq!(JavaRandom::new(0).next_float(), 0.73096776);
+    assert_eq!(JavaRandom::new(0).next_int(100), 60);
+    assert_eq!(JavaRandom::new(0).next_int(16), 11);
+
+    let mut rng = JavaRandom::new(5);
+    rng.next_long();
+    rng.set_seed(0);
+    assert_eq!(rng, JavaRandom::new(0));
+}
+
+#[test]
+fn java_random_skip() {
+    let mut stepped = JavaRandom::new(-4804349703814383506);
+    let mut skipped = stepped;
+
+    for _ in 0..1000 {
+        stepped.next_long();
+    }
+    skipped.skip(2000);
+    assert_eq!(stepped, skipped);
+
+    // The state has a period of 2^48
+    let mut full_period = stepped;
+    full_period.skip(1 << 48);
+    assert_eq!(full_period, stepped);
+}
+
+#[test]
+fn xoroshiro_matches_cubiomes() {
+    let mut rng = Xoroshiro::new(0);
+    assert_eq!(rng.next_long(), 3038984756725240190);
+    assert_eq!(rng.next_int(100), 18);
+    assert_eq!(rng.next_double(), 0.2511961888876212);
+
+    let mut skipped = Xoroshiro::new(0);
+    skipped.skip(3);
+    assert_eq!(skipped, rng);
+}
+
+#[test]
+fn random_numbers_in_bounds() {
+    let mut java = JavaRandom::new(1234);
+    let mut xoroshiro = Xoroshiro::new(1234);
+
+    for bound in 1..200 {
+        assert!((0..bound).contains(&java.next_int(bound)));
+        assert!((0..bound).contains(&xoroshiro.next_int(bound)));
+        assert!((0.0..1.0).contains(&java.next_float()));
+        assert!((0.0..1.0).contains(&xoroshiro.next_double()));
+    }
+}
+
+#[cfg(feature = "tiles")]
+fn tile_pyramid() -> crate::tiles::TilePyramid {
+    crate::tiles::TilePyramid {
+        tile_size: 256,
+        min_zoom: 0,
+        max_zoom: 4,
+        finest_scale: Scale::Block,
+        y: 64,
+        color_map: BiomeColorMap::new(),
+        workers: 2,
+    }
+}
+
+#[test]
+#[cfg(feature = "tiles")]
+fn tile_paths() {
+    use crate::tiles::Tile;
+    use std::path::Path;
+
+    let tile = Tile {
+        zoom: 3,
+        x: -1,
+        y: 2,
+    };
+
+    assert_eq!(tile.path(), Path::new("3").join("-1").join("2.png"));
+}
+
+#[test]
+#[cfg_attr(miri, ignore)]
+#[cfg(feature = "tiles")]
+fn zoom_levels_map_to_scales() {
+    use crate::tiles::Tile;
+
+    let pyramid = tile_pyramid();
+
+    let scales: Vec<_> = (0..=4)
+        .map(|zoom| pyramid.scale_for_zoom(zoom).expect("the zoom is rendered"))
+        .collect();
+    assert_eq!(
+        scales,
+        vec![
+            Scale::Chunk,
+            Scale::Chunk,
+            Scale::Quad,
+            Scale::Quad,
+            Scale::Block
+        ]
+    );
+
+    // Two pixels per position, so half the positions of the tile size
+    assert_eq!(
+        pyramid.tile_range(Tile {
+            zoom: 3,
+            x: -1,
+            y: 2
+        }),
+        Ok(Range {
+            scale: Scale::Quad,
+            x: -128,
+            z: 256,
+            size_x: 128,
+            size_z: 128,
+            y: 16,
+            size_y: 0,
+        })
+    );
+    assert_eq!(
+        pyramid.tile_range(Tile {
+            zoom: 4,
+            x: 1,
+            y: -1
+        }),
+        Ok(Range {
+            scale: Scale::Block,
+            x: 256,
+            z: -256,
+            size_x: 256,
+            size_z: 256,
+            y: 64,
+            size_y: 0,
+        })
+    );
+}
+
+#[test]
+#[cfg_attr(miri, ignore)]
+#[cfg(feature = "tiles")]
+fn tiles_cover_area() {
+    use crate::tiles::{Tile, TileError, TilePyramid};
+
+    let pyramid = tile_pyramid();
+    let area = Range {
+        scale: Scale::Block,
+        x: -300,
+        z: 0,
+        size_x: 600,
+        size_z: 100,
+        y: 64,
+        size_y: 0,
+    };
+
+    let tiles = pyramid.tiles_covering(area).expect("the pyramid is valid");
+    assert_eq!(tiles.len(), 4 + 2 + 2 + 2 + 2);
+    assert_eq!(
+        tiles[0],
+        Tile {
+            zoom: 0,
+            x: -1,
+            y: 0
+        }
+    );
+    assert!(tiles.is_sorted());
+
+    let invalid = |pyramid: TilePyramid| pyramid.tiles_covering(area).err();
+
+    assert_eq!(
+        invalid(TilePyramid {
+            tile_size: 255,
+            ..pyramid
+        }),
+        Some(TileError::InvalidTileSize(255))
+    );
+    assert_eq!(
+        invalid(TilePyramid {
+            min_zoom: 5,
+            ..pyramid
+        }),
+        Some(TileError::InvalidZoomLevels {
+            min_zoom: 5,
+            max_zoom: 4
+        })
+    );
+    // Zoom 0 would be 1:8192
+    assert_eq!(
+        invalid(TilePyramid {
+            max_zoom: 1,
+            finest_scale: Scale::OctupleRegion,
+            ..pyramid
+        }),
+        Some(TileError::ZoomOutOfRange(0))
+    );
+}
+
+#[test]
+#[cfg_attr(miri, ignore)]
+#[cfg(feature = "tiles")]
+fn tiles_are_written_as_pyramid() {
+    use crate::tiles::TilePyramid;
+
+    let generator = init_generator();
+    let dir = std::env::temp_dir().join(format!("cubiomes-tiles-{}", std::process::id()));
+
+    let pyramid = TilePyramid {
+        tile_size: 16,
+        min_zoom: 0,
+        max_zoom: 1,
+        finest_scale: Scale::Quad,
+        ..tile_pyramid()
+    };
+    let area = Range {
+        scale: Scale::Block,
+        x: -40,
+        z: -40,
+        size_x: 80,
+        size_z: 80,
+        y: 64,
+        size_y: 0,
+    };
+
+    let written = pyramid
+        .render(&generator, area, &dir)
+        .expect("the tiles can be written");
+    let tiles = pyramid.tiles_covering(area).expect("the pyramid is valid");
+    assert_eq!(written, tiles.len());
+
+    for tile in tiles {
+        let img = image::open(dir.join(tile.path()))
+            .expect("the tile was written")
+            .into_rgb8();
+        assert_eq!(img.dimensions(), (16, 16));
+
+        if tile.zoom == 1 {
+            let cache = Cache::new(
+                &generator,
+                pyramid.tile_range(tile).expect("the zoom is rendered"),
+            )
+            .expect("1:4 is supported");
+
+            assert_eq!(
+                img,
+                cache
+                    .to_image(pyramid.color_map)
+                    .expect("the biomes are valid")
+            );
+        }
+    }
+
+    std::fs::remove_dir_all(&dir).expect("the tiles can be removed");
+}
+
+#[test]
+#[cfg_attr(miri, ignore)]
+#[cfg(feature = "tiles")]
+fn unsupported_zoom_fails_before_writing_tiles() {
+    use crate::tiles::{TileError, TilePyramid};
+
+    let generator = Generator::new(
+        MCVersion::MC_1_16_5,
+        -4804349703814383506,
+        Dimension::DIM_OVERWORLD,
+        GeneratorFlags::empty(),
+    );
+    let dir =
+        std::env::temp_dir().join(format!("cubiomes-unsupported-tiles-{}", std::process::id()));
+
+    // Zoom 0 is 1024 blocks per pixel, which 1.16 can't generate
+    let pyramid = TilePyramid {
+        tile_size: 16,
+        min_zoom: 0,
+        max_zoom: 2,
+        finest_scale: Scale::HalfRegion,
+        ..tile_pyramid()
+    };
+    let area = Range {
+        scale: Scale::Block,
+        x: 0,
+        z: 0,
+        size_x: 4096,
+        size_z: 4096,
+        y: 64,
+        size_y: 0,
+    };
+
+    assert_eq!(
+        pyramid.render(&generator, area, &dir),
+        Err(TileError::Generator(GeneratorError::UnsupportedScale {
+            dimension: Dimension::DIM_OVERWORLD,
+            version: MCVersion::MC_1_16_5,
+            scale: Scale::DoubleRegion,
+        }))
+    );
+    assert!(!dir.exists());
+}
@@ -0,0 +1,443 @@
+//! Rendering biome maps as a pyramid of tiles
+//!
+//! This module requires crate feature tiles
+//!
+//! Instead of one huge image, the map is split into square PNG tiles stored
+//! as `{z}/{x}/{y}.png`, with a level of tiles for each zoom level. This is
+//! the layout web map libraries like Leaflet load maps from, so the tiles can
+//! be hosted as a seed map without a server generating biomes.
+//!
+//! Tile (0, 0) has its north west corner at the world origin, x grows to the
+//! east and y to the south. Tiles west or north of the origin have negative
+//! coordinates. In Leaflet use `L.CRS.Simple`, where a map unit is then
+//! `finest_scale * 2^max_zoom` blocks and the latitude is minus the block z
+//! coordinate.
+//!
+//! # Usage
+//! ```
+#![doc = include_str!("../examples/render_tiles.rs")]
+//! ```
+
+use std::{
+    fs::{self, File},
+    io::{self, BufWriter, Write},
+    path::{Path, PathBuf},
+    sync::{
+        atomic::{AtomicBool, AtomicUsize, Ordering},
+        Mutex, PoisonError,
+    },
+    thread,
+};
+
+use image::{imageops::FilterType, ImageFormat, RgbImage};
+use thiserror::Error;
+
+use crate::{
+    colors::BiomeColorMap,
+    generator::{error::GeneratorError, Cache, Generator, Range, Scale},
+};
+
+/// Scales which tiles can be generated at, from fine to coarse
+const SCALES: [Scale; 7] = [
+    Scale::Block,
+    Scale::Quad,
+    Scale::Chunk,
+    Scale::QuadChunk,
+    Scale::HalfRegion,
+    Scale::DoubleRegion,
+    Scale::OctupleRegion,
+];
+
+/// An error rendering tiles
+#[derive(Error, Debug, Clone, PartialEq, Eq)]
+pub enum TileError {
+    /// The tile size is zero or odd
+    #[error("tile size {0} is not a positive even number")]
+    InvalidTileSize(u32),
+    /// The lowest zoom level is above the highest one
+    #[error("minimum zoom {min_zoom} is above the maximum zoom {max_zoom}")]
+    InvalidZoomLevels {
+        /// The lowest zoom level
+        min_zoom: u8,
+        /// The highest zoom level
+        max_zoom: u8,
+    },
+    /// The zoom level would need a scale coarser than
+    /// [`Scale::OctupleRegion`]
+    #[error("zoom level {0} is too far out for the coarsest scale")]
+    ZoomOutOfRange(u8),
+    /// Generating the biomes of a tile failed
+    #[error(transparent)]
+    Generator(GeneratorError),
+    /// Writing a tile or creating its directory failed
+    #[error("failed to write {}: {kind}", path.display())]
+    Write {
+        /// The file or directory which couldn't be written
+        path: PathBuf,
+        /// What went wrong
+        kind: io::ErrorKind,
+    },
+}
+
+impl From<GeneratorError> for TileError {
+    fn from(value: GeneratorError) -> Self {
+        Self::Generator(value)
+    }
+}
+
+/// A tile in a [`TilePyramid`]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct Tile {
+    /// The zoom level of the tile
+    pub zoom: u8,
+    /// The column of the tile, growing to the east
+    pub x: i32,
+    /// The row of the tile, growing to the south
+    pub y: i32,
+}
+
+impl Tile {
+    /// Gets the path of the tile relative to the root of the pyramid, eg.
+    /// `{z}/{x}/{y}.png`
+    #[must_use]
+    pub fn path(&self) -> PathBuf {
+        [
+            self.zoom.to_string(),
+            self.x.to_string(),
+            format!("{}.png", self.y),
+        ]
+        .iter()
+        .collect()
+    }
+}
+
+/// Settings for rendering biome maps as tiles
+///
+/// At [`Self::max_zoom`] a pixel covers [`Self::finest_scale`] blocks, and
+/// every zoom level out doubles the blocks per pixel. Each zoom level is
+/// generated at the finest [`Scale`] which isn't finer than a pixel, see
+/// [`Self::scale_for_zoom()`]. When a position of the scale is two pixels
+/// wide, the tile is upscaled.
+#[derive(Debug, Clone, Copy)]
+pub struct TilePyramid {
+    /// Width and height of the tiles in pixels, must be even
+    pub tile_size: u32,
+    /// The most zoomed out level to render
+    pub min_zoom: u8,
+    /// The most zoomed in level to render
+    pub max_zoom: u8,
+    /// Blocks per pixel at [`Self::max_zoom`]
+    pub finest_scale: Scale,
+    /// Block y coordinate the biomes are generated at
+    pub y: i32,
+    /// Colors of the biomes
+    pub color_map: BiomeColorMap,
+    /// Amount of threads rendering tiles, 0 uses one thread for each core
+    pub workers: usize,
+}
+
+impl Default for TilePyramid {
+    /// 256 pixel tiles with zoom levels 0 to 6, where 6 is one block per
+    /// pixel
+    fn default() -> Self {
+        Self {
+            tile_size: 256,
+            min_zoom: 0,
+            max_zoom: 6,
+            finest_scale: Scale::Block,
+            y: 64,
+            color_map: BiomeColorMap::new(),
+            workers: 0,
+        }
+    }
+}
+
+impl TilePyramid {
+    /// Checks that the tile size and zoom levels can be rendered
+    ///
+    /// # Errors
+    /// Returns [`TileError::InvalidTileSize`] if the tile size is zero or odd,
+    /// [`TileError::InvalidZoomLevels`] if the minimum zoom is above the
+    /// maximum, and [`TileError::ZoomOutOfRange`] if the minimum zoom would
+    /// need a scale coarser than [`Scale::OctupleRegion`].
+    pub fn validate(&self) -> Result<(), TileError> {
+        if self.tile_size == 0 || !self.tile_size.is_multiple_of(2) {
+            return Err(TileError::InvalidTileSize(self.tile_size));
+        }
+
+        if self.min_zoom > self.max_zoom {
+            return Err(TileError::InvalidZoomLevels {
+                min_zoom: self.min_zoom,
+                max_zoom: self.max_zoom,
+            });
+        }
+
+        self.scale_for_zoom(self.min_zoom).map(|_| ())
+    }
+
+    /// Gets how many blocks a pixel covers at the zoom level
+    ///
+    /// Returns [`None`] if the zoom level is above [`Self::max_zoom`] or the
+    /// amount doesn't fit in an [`u32`].
+    #[must_use]
+    pub fn blocks_per_pixel(&self, zoom: u8) -> Option<u32> {
+        let zoom_out = self.max_zoom.checked_sub(zoom)?;
+
+        (self.finest_scale as u32).checked_mul(2u32.checked_pow(zoom_out as u32)?)
+    }
+
+    /// Gets the scale the biomes of the zoom level are generated at
+    ///
+    /// This is the finest scale where a position is at least a pixel wide,
+    /// so a position covers one or two pixels.
+    ///
+    /// # Errors
+    /// Returns [`TileError::ZoomOutOfRange`] if the zoom level is above
+    /// [`Self::max_zoom`] or would need a scale coarser than
+    /// [`Scale::OctupleRegion`].
+    pub fn scale_for_zoom(&self, zoom: u8) -> Result<Scale, TileError> {
+        let blocks_per_pixel = self
+            .blocks_per_pixel(zoom)
+            .ok_or(TileError::ZoomOutOfRange(zoom))?;
+
+        SCALES
+            .into_iter()
+            .find(|scale| *scale as u32 >= blocks_per_pixel)
+            .ok_or(TileError::ZoomOutOfRange(zoom))
+    }
+
+    /// Gets the range of biomes rendered into the tile
+    ///
+    /// # Errors
+    /// Returns [`TileError::ZoomOutOfRange`] if the zoom level of the tile
+    /// isn't rendered, see [`Self::scale_for_zoom()`].
+    pub fn tile_range(&self, tile: Tile) -> Result<Range, TileError> {
+        let scale = self.scale_for_zoom(tile.zoom)?;
+        let blocks_per_pixel = self
+            .blocks_per_pixel(tile.zoom)
+            .ok_or(TileError::ZoomOutOfRange(tile.zoom))?;
+
+        // A position is one or two pixels wide, and the tile size is even
+        let size = self.tile_size / (scale as u32 / blocks_per_pixel);
+
+        let y = match scale {
+            Scale::Block => self.y,
+            _ => self.y.div_euclid(4),
+        };
+
+        Ok(Range {
+            scale,
+            x: tile.x.wrapping_mul(size as i32),
+            z: tile.y.wrapping_mul(size as i32),
+            size_x: size,
+            size_z: size,
+            y,
+            size_y: 0,
+        })
+    }
+
+    /// Gets every tile covering the area on each zoom level
+    ///
+    /// The tiles are sorted by zoom level, then by column and row.
+    ///
+    /// # Errors
+    /// Returns the errors of [`Self::validate()`]
+    pub fn tiles_covering(&self, area: Range) -> Result<Vec<Tile>, TileError> {
+        self.validate()?;
+
+        let min = area.min_block_position();
+        let max = area.max_block_position();
+
+        let mut tiles = Vec::new();
+
+        for zoom in self.min_zoom..=self.max_zoom {
+            let blocks_per_pixel = self
+                .blocks_per_pixel(zoom)
+                .ok_or(TileError::ZoomOutOfRange(zoom))?;
+            let tile_blocks = self.tile_size as i64 * blocks_per_pixel as i64;
+
+            let tile_coords = |min: i32, max: i32| {
+                (min as i64).div_euclid(tile_blocks) as i32
+                    ..=(max as i64 - 1).div_euclid(tile_blocks) as i32
+            };
+
+            for x in tile_coords(min.x, max.x) {
+                for y in tile_coords(min.z, max.z) {
+                    tiles.push(Tile { zoom, x, y });
+                }
+            }
+        }
+
+        Ok(tiles)
+    }
+
+    /// Renders the biomes of a cache into a tile image
+    ///
+    /// The cache should cover the [`Self::tile_range()`] of a tile. If a
+    /// position is two pixels wide, the image is upscaled to the tile size.
+    ///
+    /// # Errors
+    /// Returns an error if the cache contains a value which is not a valid
+    /// biome.
+    pub fn render_tile(&self, cache: &Cache) -> Result<RgbImage, TileError> {
+        let img = cache.to_image(self.color_map)?;
+
+        if img.dimensions() == (self.tile_size, self.tile_size) {
+            return Ok(img);
+        }
+
+        Ok(image::imageops::resize(
+            &img,
+            self.tile_size,
+            self.tile_size,
+            FilterType::Nearest,
+        ))
+    }
+
+    /// Renders every tile covering the area into the directory
+    ///
+    /// The tiles are written to `{z}/{x}/{y}.png` inside `dir`, see
+    /// [`Tile::path()`]. Existing tiles are overwritten. The tiles are split
+    /// between [`Self::workers`] threads, each of which reuses a single cache
+    /// allocation for all of its tiles.
+    ///
+    /// Returns the amount of tiles written. If rendering a tile fails, the
+    /// workers stop and the first error which happened is returned.
+    ///
+    /// # Errors
+    /// Returns the errors of [`Self::validate()`],
+    /// [`TileError::Generator`] if the generator can't generate the scale of a
+    /// zoom level and [`TileError::Write`] if writing a tile fails. The scales
+    /// of all zoom levels are checked with [`Generator::validate_range()`]
+    /// before any tile is written.
+    pub fn render(
+        &self,
+        generator: &Generator,
+        area: Range,
+        dir: &Path,
+    ) -> Result<usize, TileError> {
+        let tiles = self.tiles_covering(area)?;
+
+        for zoom in self.min_zoom..=self.max_zoom {
+            generator.validate_range(&self.tile_range(Tile { zoom, x: 0, y: 0 })?)?;
+        }
+
+        let workers = match self.workers {
+            0 => thread::available_parallelism().map_or(1, |workers| workers.get()),
+            workers => workers,
+        }
+        .clamp(1, tiles.len().max(1));
+
+        let next_tile = AtomicUsize::new(0);
+        let failed = AtomicBool::new(false);
+        // Workers stopping because of the failure may fail as well, so only
+        // the error which happened first is kept
+        let first_error: Mutex<Option<TileError>> = Mutex::new(None);
+
+        let written = thread::scope(|scope| {
+            let handles: Vec<_> = (0..workers)
+                .map(|_| {
+                    scope.spawn(|| {
+                        match self.render_worker(generator, &tiles, &next_tile, &failed, dir) {
+                            Ok(written) => written,
+                            Err(err) => {
+                                first_error
+                                    .lock()
+                                    .unwrap_or_else(PoisonError::into_inner)
+                                    .get_or_insert(err);
+                                failed.store(true, Ordering::Relaxed);
+
+                                0
+                            }
+                        }
+                    })
+                })
+                .collect();
+
+            handles
+                .into_iter()
+                .map(|handle| {
+                    handle
+                        .join()
+                        .unwrap_or_else(|panic| std::panic::resume_unwind(panic))
+                })
+                .sum()
+        });
+
+        match first_error
+            .into_inner()
+            .unwrap_or_else(PoisonError::into_inner)
+        {
+            Some(err) => Err(err),
+            None => Ok(written),
+        }
+    }
+
+    /// Renders tiles until all are taken by the workers or one of them fails
+    fn render_worker(
+        &self,
+        generator: &Generator,
+        tiles: &[Tile],
+        next_tile: &AtomicUsize,
+        failed: &AtomicBool,
+        dir: &Path,
+    ) -> Result<usize, TileError> {
+        let mut cache: Option<Cache> = None;
+        let mut written = 0;
+
+        while !failed.load(Ordering::Relaxed) {
+            let Some(tile) = tiles.get(next_tile.fetch_add(1, Ordering::Relaxed)) else {
+                break;
+            };
+
+            let range = self.tile_range(*tile)?;
+
+            // Tiles of the same zoom level have the same scale and size, so
+            // the cache only needs to be moved
+            let tile_cache = match cache.take() {
+                Some(mut cache) if same_size(cache.range(), &range) => {
+                    cache.move_cache(range.x, range.y, range.z)?;
+                    cache
+                }
+                Some(cache) => Cache::from_buffer(generator, range, cache.into_buffer())?,
+                None => Cache::new(generator, range)?,
+            };
+
+            let img = self.render_tile(&tile_cache)?;
+            cache = Some(tile_cache);
+
+            write_png(&img, &dir.join(tile.path()))?;
+            written += 1;
+        }
+
+        Ok(written)
+    }
+}
+
+fn same_size(a: &Range, b: &Range) -> bool {
+    (a.scale, a.size_x, a.size_z, a.size_y) == (b.scale, b.size_x, b.size_z, b.size_y)
+}
+
+fn write_png(img: &RgbImage, path: &Path) -> Result<(), TileError> {
+    let write_error = |kind| TileError::Write {
+        path: path.to_path_buf(),
+        kind,
+    };
+
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent).map_err(|err| TileError::Write {
+            path: parent.to_path_buf(),
+            kind: err.kind(),
+        })?;
+    }
+
+    let mut file = BufWriter::new(File::create(path).map_err(|err| write_error(err.kind()))?);
+
+    img.write_to(&mut file, ImageFormat::Png)
+        .map_err(|err| match err {
+            image::ImageError::IoError(err) => write_error(err.kind()),
+            _ => write_error(io::ErrorKind::Other),
+        })?;
+
+    file.flush().map_err(|err| write_error(err.kind()))
+}
@@ -22,13 +22,14 @@ fn get_random_point(rng: &mut (impl SeedableRng + Rng)) -> (i32, i32) {
 }
 
 #[test]
+#[cfg_attr(miri, ignore)]
 fn test_random_points() {
     let mut rng = SmallRng::seed_from_u64(SEED);
 
     for _ in 0..POINT_AMOUNT {
         let generator = Generator::new(
             MCVersion::MC_1_21_WD,
-            rng.random(),
+            rng.random::<i64>(),
             Dimension::DIM_OVERWORLD,
             GeneratorFlags::empty(),
         );